@@ -0,0 +1,32 @@
+use bevy_rapier2d::prelude::*;
+
+pub const PLAYER_GROUP: Group = Group::GROUP_1;
+pub const ENEMY_GROUP: Group = Group::GROUP_2;
+pub const PLAYER_PROJECTILE_GROUP: Group = Group::GROUP_3;
+pub const ENEMY_PROJECTILE_GROUP: Group = Group::GROUP_4;
+
+/// The player only bumps into enemies and is hit by enemy projectiles.
+pub const PLAYER_COLLISION_GROUPS: CollisionGroups =
+    CollisionGroups::new(PLAYER_GROUP, ENEMY_GROUP.union(ENEMY_PROJECTILE_GROUP));
+pub const ENEMY_COLLISION_GROUPS: CollisionGroups = CollisionGroups::new(
+    ENEMY_GROUP,
+    PLAYER_GROUP
+        .union(ENEMY_GROUP)
+        .union(PLAYER_PROJECTILE_GROUP),
+);
+/// Player projectiles only interact with enemies.
+pub const PLAYER_PROJECTILE_COLLISION_GROUPS: CollisionGroups =
+    CollisionGroups::new(PLAYER_PROJECTILE_GROUP, ENEMY_GROUP);
+/// Enemy projectiles only interact with the player.
+pub const ENEMY_PROJECTILE_COLLISION_GROUPS: CollisionGroups =
+    CollisionGroups::new(ENEMY_PROJECTILE_GROUP, PLAYER_GROUP);
+
+/// Filter for scene queries done by player weapons. Only finds enemies.
+pub fn enemy_filter() -> QueryFilter<'static> {
+    QueryFilter::only_dynamic().groups(PLAYER_PROJECTILE_COLLISION_GROUPS)
+}
+
+/// Filter for scene queries done by enemy projectiles. Only finds the player.
+pub fn player_filter() -> QueryFilter<'static> {
+    QueryFilter::only_dynamic().groups(ENEMY_PROJECTILE_COLLISION_GROUPS)
+}
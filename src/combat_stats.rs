@@ -0,0 +1,96 @@
+use std::collections::{HashMap, VecDeque};
+
+use bevy::prelude::*;
+
+use crate::{
+    damage::{DamageDealtEvent, EnemyDeathEvent},
+    guns::{WeaponFireEvent, WeaponKind},
+    GameState,
+};
+
+/// Time window in seconds over which DPS is averaged.
+pub const DPS_WINDOW: f32 = 5.0;
+
+pub struct CombatStatsPlugin;
+
+impl Plugin for CombatStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CombatStats>()
+            .add_system(reset_combat_stats.in_schedule(OnExit(GameState::MainMenu)))
+            .add_system(reset_combat_stats.in_schedule(OnExit(GameState::GameOver)))
+            .add_system(collect_combat_stats.in_set(OnUpdate(GameState::InGame)));
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WeaponStats {
+    pub damage: i64,
+    pub kills: u32,
+    pub shots: u32,
+    pub hits: u32,
+    /// Damage in the last `DPS_WINDOW` seconds divided by the window.
+    pub dps: f32,
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct CombatStats {
+    /// Seconds spent in game this run.
+    pub time: f32,
+    pub weapons: HashMap<WeaponKind, WeaponStats>,
+    recent_damage: VecDeque<(f32, WeaponKind, i32)>,
+}
+
+impl CombatStats {
+    pub fn total_kills(&self) -> u32 {
+        self.weapons.values().map(|stats| stats.kills).sum()
+    }
+}
+
+fn reset_combat_stats(mut stats: ResMut<CombatStats>) {
+    *stats = CombatStats::default();
+}
+
+fn collect_combat_stats(
+    time: Res<Time>,
+    mut stats: ResMut<CombatStats>,
+    mut fire_events: EventReader<WeaponFireEvent>,
+    mut damage_events: EventReader<DamageDealtEvent>,
+    mut death_events: EventReader<EnemyDeathEvent>,
+) {
+    let now = time.elapsed_seconds();
+    stats.time += time.delta_seconds();
+
+    for event in fire_events.iter() {
+        stats.weapons.entry(event.source.kind).or_default().shots += event.projectiles;
+    }
+    for event in damage_events.iter() {
+        let weapon = stats.weapons.entry(event.source.kind).or_default();
+        weapon.damage += event.damage as i64;
+        if !event.from_effect {
+            weapon.hits += 1;
+        }
+        stats
+            .recent_damage
+            .push_back((now, event.source.kind, event.damage));
+    }
+    for event in death_events.iter() {
+        stats.weapons.entry(event.killer.kind).or_default().kills += 1;
+    }
+
+    while let Some((t, _, _)) = stats.recent_damage.front() {
+        if now - t <= DPS_WINDOW {
+            break;
+        }
+        stats.recent_damage.pop_front();
+    }
+
+    let stats = stats.as_mut();
+    for weapon in stats.weapons.values_mut() {
+        weapon.dps = 0.0;
+    }
+    for (_, kind, damage) in stats.recent_damage.iter() {
+        if let Some(weapon) = stats.weapons.get_mut(kind) {
+            weapon.dps += *damage as f32 / DPS_WINDOW;
+        }
+    }
+}
@@ -1,6 +1,11 @@
 use bevy::prelude::*;
 
-use crate::{enemy::Enemy, player::Player, GameState};
+use crate::{
+    enemy::{Enemy, EnemyQuality},
+    guns::WeaponKind,
+    player::Player,
+    GameState,
+};
 
 pub struct DamagePlugin;
 
@@ -8,10 +13,19 @@ impl Plugin for DamagePlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<PlayerDamageEvent>()
             .add_event::<EnemyDamageEvent>()
+            .add_event::<EnemyDeathEvent>()
+            .add_event::<DamageDealtEvent>()
             .add_systems((damage_enemy, damage_player).in_set(OnUpdate(GameState::InGame)));
     }
 }
 
+/// What dealt the damage: the weapon entity and its kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DamageSource {
+    pub weapon: Entity,
+    pub kind: WeaponKind,
+}
+
 #[derive(Debug)]
 pub struct PlayerDamageEvent {
     pub damage: i32,
@@ -21,12 +35,55 @@ pub struct PlayerDamageEvent {
 pub struct EnemyDamageEvent {
     pub target: Entity,
     pub damage: i32,
+    pub crit: bool,
+    pub source: DamageSource,
+    /// Damage dealt by an on-hit or on-kill effect rather than by the weapon itself.
+    pub from_effect: bool,
+}
+
+/// Damage actually taken by a living enemy, without overkill.
+#[derive(Debug)]
+pub struct DamageDealtEvent {
+    pub damage: i32,
+    pub source: DamageSource,
+    pub from_effect: bool,
+}
+
+/// Sent once when an enemy's health drops to zero.
+#[derive(Debug)]
+pub struct EnemyDeathEvent {
+    pub entity: Entity,
+    pub position: Vec3,
+    pub quality: EnemyQuality,
+    pub killer: DamageSource,
 }
 
-fn damage_enemy(mut events: EventReader<EnemyDamageEvent>, mut enemies: Query<&mut Enemy>) {
+fn damage_enemy(
+    mut events: EventReader<EnemyDamageEvent>,
+    mut death_event: EventWriter<EnemyDeathEvent>,
+    mut dealt_event: EventWriter<DamageDealtEvent>,
+    mut enemies: Query<(&Transform, &mut Enemy)>,
+) {
     for event in events.iter() {
-        if let Ok(mut enemy) = enemies.get_mut(event.target) {
+        if let Ok((transform, mut enemy)) = enemies.get_mut(event.target) {
+            // already dead, waiting to be despawned
+            if enemy.health <= 0 {
+                continue;
+            }
+            dealt_event.send(DamageDealtEvent {
+                damage: event.damage.min(enemy.health),
+                source: event.source,
+                from_effect: event.from_effect,
+            });
             enemy.health -= event.damage;
+            if enemy.health <= 0 {
+                death_event.send(EnemyDeathEvent {
+                    entity: event.target,
+                    position: transform.translation,
+                    quality: enemy.quality,
+                    killer: event.source,
+                });
+            }
         }
     }
 }
@@ -0,0 +1,212 @@
+use bevy::{
+    ecs::{event::ManualEventReader, system::SystemParam},
+    prelude::*,
+};
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    collision::enemy_filter,
+    damage::{DamageSource, EnemyDamageEvent, EnemyDeathEvent, PlayerDamageEvent},
+    enemy::Enemy,
+    guns::nearest_enemy,
+    player::Player,
+    stats::{Modifier, ModifierKind, ModifierSource, Stat, Stats},
+    GameState,
+};
+
+pub struct EffectsPlugin;
+
+impl Plugin for EffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(trigger_effects.in_set(OnUpdate(GameState::InGame)));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum EffectTrigger {
+    Hit,
+    Crit,
+    Kill,
+    DamageTaken,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum Effect {
+    /// Heals the player by a fraction of the damage.
+    Lifesteal { fraction: f32 },
+    /// Damages the nearest other enemy within range.
+    Chain { damage: i32, range: f32 },
+    /// Damages every other enemy within radius.
+    Explosion { damage: i32, radius: f32 },
+    /// Temporarily increases player speed, a new boost replaces the last.
+    SpeedBoost { speed: f32, duration: f32 },
+}
+
+/// Effects registered by items (on the player) or by weapons (on the weapon entity).
+#[derive(Component, Default)]
+pub struct Effects {
+    pub effects: Vec<(EffectTrigger, Effect)>,
+}
+
+struct Trigger {
+    trigger: EffectTrigger,
+    source: Option<DamageSource>,
+    target: Option<Entity>,
+    position: Vec3,
+    damage: i32,
+}
+
+/// Entities effects are looked up on or applied to.
+#[derive(SystemParam)]
+struct EffectQueries<'w, 's> {
+    effects: Query<'w, 's, &'static Effects>,
+    enemies: Query<'w, 's, &'static Transform, With<Enemy>>,
+    player: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Transform,
+            &'static mut Player,
+            &'static mut Stats,
+        ),
+    >,
+}
+
+fn trigger_effects(
+    rapier_context: Res<RapierContext>,
+    queries: EffectQueries,
+    mut hit_reader: Local<ManualEventReader<EnemyDamageEvent>>,
+    mut enemy_damage_events: ResMut<Events<EnemyDamageEvent>>,
+    mut enemy_death_events: EventReader<EnemyDeathEvent>,
+    mut player_damage_events: EventReader<PlayerDamageEvent>,
+) {
+    let EffectQueries {
+        effects,
+        enemies,
+        mut player,
+    } = queries;
+    let (player_entity, player_transform, mut player, mut stats) = player.single_mut();
+
+    let mut triggers = Vec::new();
+    // effect damage does not trigger other effects
+    for event in hit_reader
+        .iter(&enemy_damage_events)
+        .filter(|event| !event.from_effect)
+    {
+        let Ok(transform) = enemies.get(event.target) else {
+            continue;
+        };
+        triggers.push(Trigger {
+            trigger: EffectTrigger::Hit,
+            source: Some(event.source),
+            target: Some(event.target),
+            position: transform.translation,
+            damage: event.damage,
+        });
+        if event.crit {
+            triggers.push(Trigger {
+                trigger: EffectTrigger::Crit,
+                source: Some(event.source),
+                target: Some(event.target),
+                position: transform.translation,
+                damage: event.damage,
+            });
+        }
+    }
+    for event in enemy_death_events.iter() {
+        triggers.push(Trigger {
+            trigger: EffectTrigger::Kill,
+            source: Some(event.killer),
+            target: Some(event.entity),
+            position: event.position,
+            damage: 0,
+        });
+    }
+    for event in player_damage_events.iter() {
+        triggers.push(Trigger {
+            trigger: EffectTrigger::DamageTaken,
+            source: None,
+            target: None,
+            position: player_transform.translation,
+            damage: event.damage,
+        });
+    }
+
+    let mut damage = Vec::new();
+    for trigger in triggers.iter() {
+        // weapon effects and player item effects, without counting the player twice
+        let weapon = trigger.source.map(|source| source.weapon);
+        let holders = std::iter::once(player_entity).chain(weapon.filter(|w| *w != player_entity));
+
+        for (effect_trigger, effect) in holders
+            .filter_map(|holder| effects.get(holder).ok())
+            .flat_map(|effects| effects.effects.iter())
+        {
+            if *effect_trigger != trigger.trigger {
+                continue;
+            }
+            match *effect {
+                Effect::Lifesteal { fraction } => {
+                    let heal = (trigger.damage as f32 * fraction).ceil() as i32;
+                    let max_health = stats.get(Stat::MaxHealth).round() as i32;
+                    player.health = (player.health + heal).min(max_health);
+                }
+                // chain and explosion need a weapon to attribute their damage to
+                Effect::Chain { damage: d, range } => {
+                    let Some(source) = trigger.source else {
+                        continue;
+                    };
+                    let nearest =
+                        nearest_enemy(&rapier_context, &enemies, trigger.position, range, |e| {
+                            Some(e) == trigger.target
+                        });
+                    if let Some((target, _)) = nearest {
+                        damage.push(EnemyDamageEvent {
+                            target,
+                            damage: d,
+                            crit: false,
+                            source,
+                            from_effect: true,
+                        });
+                    }
+                }
+                Effect::Explosion { damage: d, radius } => {
+                    let Some(source) = trigger.source else {
+                        continue;
+                    };
+                    rapier_context.intersections_with_shape(
+                        trigger.position.truncate(),
+                        0.0,
+                        &Collider::ball(radius),
+                        enemy_filter(),
+                        |e| {
+                            if Some(e) != trigger.target && enemies.contains(e) {
+                                damage.push(EnemyDamageEvent {
+                                    target: e,
+                                    damage: d,
+                                    crit: false,
+                                    source,
+                                    from_effect: true,
+                                });
+                            }
+                            true
+                        },
+                    );
+                }
+                Effect::SpeedBoost { speed, duration } => {
+                    stats.set_timed(
+                        ModifierSource::Buff,
+                        Modifier {
+                            stat: Stat::MoveSpeed,
+                            kind: ModifierKind::Add(speed),
+                        },
+                        duration,
+                    );
+                }
+            }
+        }
+    }
+    enemy_damage_events.extend(damage);
+}
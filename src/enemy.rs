@@ -1,9 +1,14 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
 
 use crate::{
-    damage::PlayerDamageEvent,
+    collision::ENEMY_COLLISION_GROUPS,
+    damage::{EnemyDeathEvent, PlayerDamageEvent},
+    hit_feedback::HitFlash,
+    patterns::{Emitter, Patterns, Team},
     player::{CharacterBundle, Player},
+    stats::{Modifier, ModifierKind, ModifierSource, Stat, Stats},
     utils::remove_all_with,
     GameAssets, GameState,
 };
@@ -21,13 +26,23 @@ pub struct EnemyPlugin;
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            (enemy_spawn, enemy_movement, enemy_damage, enemy_despawn)
+            (
+                enemy_spawn,
+                enemy_movement,
+                enemy_damage,
+                enemy_despawn,
+                enemy_drop_exp,
+            )
                 .in_set(OnUpdate(GameState::InGame)),
         )
-        .add_system(remove_all_with::<EnemyMarker>.in_schedule(OnEnter(GameState::MainMenu)));
+        .add_system(remove_all_with::<EnemyMarker>.in_schedule(OnEnter(GameState::MainMenu)))
+        .add_system(remove_all_with::<EnemyMarker>.in_schedule(OnExit(GameState::GameOver)))
+        .add_system(remove_all_with::<Experience>.in_schedule(OnEnter(GameState::MainMenu)))
+        .add_system(remove_all_with::<Experience>.in_schedule(OnExit(GameState::GameOver)));
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
 pub enum EnemyQuality {
     Common,
     Rare,
@@ -38,7 +53,6 @@ pub enum EnemyQuality {
 #[derive(Component)]
 pub struct Enemy {
     pub health: i32,
-    pub speed: f32,
     pub quality: EnemyQuality,
     pub distance_to_player: f32,
 }
@@ -69,25 +83,29 @@ pub struct Experience {
 pub struct EnemyBundle {
     character: CharacterBundle,
     enemy: Enemy,
+    stats: Stats,
     attack: EnemyAttack,
+    hit_flash: HitFlash,
     marker: EnemyMarker,
 }
 
 impl EnemyBundle {
-    fn new(quality: EnemyQuality) -> Self {
+    /// Starts at the `MaxHealth` of `stats`.
+    fn new(quality: EnemyQuality, stats: Stats) -> Self {
         Self {
-            character: CharacterBundle::default(),
+            character: CharacterBundle::new(ENEMY_COLLISION_GROUPS),
             enemy: Enemy {
-                health: ENEMY_HEALTH,
-                speed: ENEMY_SPEED,
+                health: stats.get(Stat::MaxHealth).round() as i32,
                 quality,
                 distance_to_player: f32::MAX,
             },
+            stats,
             attack: EnemyAttack {
                 damage: ENEMY_ATTACK_DAMAGE,
                 range: ENEMY_ATTACK_RADIUS,
                 timer: Timer::from_seconds(ENEMY_ATTACK_SPEED, TimerMode::Repeating),
             },
+            hit_flash: HitFlash::default(),
             marker: EnemyMarker,
         }
     }
@@ -96,18 +114,21 @@ impl EnemyBundle {
 fn enemy_spawn(
     time: Res<Time>,
     game_assets: Res<GameAssets>,
+    patterns: Res<Patterns>,
     mut commands: Commands,
-    mut wave: Query<(&Transform, &mut EnemyWave), With<Player>>,
+    mut wave: Query<(&Transform, &Stats, &mut EnemyWave), With<Player>>,
 ) {
-    let (player_transform, mut wave) = wave.single_mut();
+    let (player_transform, stats, mut wave) = wave.single_mut();
 
     if !wave.timer.tick(time.delta()).finished() {
         return;
     }
 
-    for n in 0..wave.number {
+    let curse = stats.get(Stat::Curse);
+    let number = (wave.number as f32 * curse).round() as u32;
+    for n in 0..number {
         let position = player_transform.translation
-            + Quat::from_rotation_z((2.0 * std::f32::consts::PI / wave.number as f32) * n as f32)
+            + Quat::from_rotation_z((2.0 * std::f32::consts::PI / number as f32) * n as f32)
                 .mul_vec3(Vec3::Y * wave.radius);
 
         let quality = match rand::random::<u8>() % 4 {
@@ -117,30 +138,43 @@ fn enemy_spawn(
             3 => EnemyQuality::Legendary,
             _ => unreachable!(),
         };
-        commands
-            .spawn(SpriteBundle {
-                transform: Transform::from_translation(position),
-                texture: game_assets.enemy.clone(),
-                ..default()
-            })
-            .insert(EnemyBundle::new(quality));
+        let mut enemy = commands.spawn(SpriteBundle {
+            transform: Transform::from_translation(position),
+            texture: game_assets.enemy.clone(),
+            ..default()
+        });
+        let mut enemy_stats = Stats::new([
+            (Stat::MaxHealth, ENEMY_HEALTH as f32),
+            (Stat::MoveSpeed, ENEMY_SPEED),
+        ]);
+        enemy_stats.add(
+            ModifierSource::Curse,
+            Modifier {
+                stat: Stat::MaxHealth,
+                kind: ModifierKind::Mul(curse),
+            },
+        );
+        enemy.insert(EnemyBundle::new(quality, enemy_stats));
+        if let Some(pattern) = patterns.for_enemy(quality) {
+            enemy.insert(Emitter::new(pattern, Team::Enemy));
+        }
     }
 }
 
 fn enemy_movement(
     time: Res<Time>,
     player: Query<&Transform, With<Player>>,
-    mut enemies: Query<(&Transform, &mut Enemy, &mut Velocity)>,
+    mut enemies: Query<(&Transform, &Stats, &mut Enemy, &mut Velocity)>,
 ) {
     let player_transform = player.single();
 
-    for (enemy_transform, mut enemy, mut enemy_velocity) in enemies.iter_mut() {
+    for (enemy_transform, stats, mut enemy, mut enemy_velocity) in enemies.iter_mut() {
         let vector = (player_transform.translation - enemy_transform.translation).truncate();
         let distance = vector.length();
         let direction = vector.normalize();
         let movement = direction * time.delta().as_secs_f32();
 
-        enemy_velocity.linvel = movement * enemy.speed * ENEMY_MOVEMENT_FORCE;
+        enemy_velocity.linvel = movement * stats.get(Stat::MoveSpeed) * ENEMY_MOVEMENT_FORCE;
         enemy.distance_to_player = distance;
     }
 }
@@ -162,27 +196,30 @@ fn enemy_damage(
     }
 }
 
-fn enemy_despawn(
+fn enemy_despawn(mut commands: Commands, mut death_events: EventReader<EnemyDeathEvent>) {
+    for event in death_events.iter() {
+        commands.entity(event.entity).despawn();
+    }
+}
+
+fn enemy_drop_exp(
     game_assets: Res<GameAssets>,
     mut commands: Commands,
-    enemies: Query<(Entity, &Enemy, &Transform)>,
+    mut death_events: EventReader<EnemyDeathEvent>,
 ) {
-    for (entity, enemy, transform) in enemies.iter() {
-        if enemy.health <= 0 {
-            commands.entity(entity).despawn();
-            let texture = match enemy.quality {
-                EnemyQuality::Common => game_assets.exp_common.clone(),
-                EnemyQuality::Rare => game_assets.exp_rare.clone(),
-                EnemyQuality::Epic => game_assets.exp_epic.clone(),
-                EnemyQuality::Legendary => game_assets.exp_legendary.clone(),
-            };
-            commands
-                .spawn(SpriteBundle {
-                    transform: *transform,
-                    texture,
-                    ..default()
-                })
-                .insert(Experience { exp: 10 });
-        }
+    for event in death_events.iter() {
+        let texture = match event.quality {
+            EnemyQuality::Common => game_assets.exp_common.clone(),
+            EnemyQuality::Rare => game_assets.exp_rare.clone(),
+            EnemyQuality::Epic => game_assets.exp_epic.clone(),
+            EnemyQuality::Legendary => game_assets.exp_legendary.clone(),
+        };
+        commands
+            .spawn(SpriteBundle {
+                transform: Transform::from_translation(event.position),
+                texture,
+                ..default()
+            })
+            .insert(Experience { exp: 10 });
     }
 }
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    effects::{Effect, EffectTrigger},
+    guns::{Weapon, WeaponKind, WeaponLevels},
+    stats::{Modifier, Stat, Stats},
+};
+
+/// Recipes offered on level up once their conditions are met.
+const EVOLUTIONS: &str = include_str!("../assets/evolutions.ron");
+
+pub struct EvolutionPlugin;
+
+impl Plugin for EvolutionPlugin {
+    fn build(&self, app: &mut App) {
+        let weapon_levels = app
+            .world
+            .get_resource::<WeaponLevels>()
+            .expect("EvolutionPlugin must be added after GunsPlugin");
+        let evolutions =
+            Evolutions::from_ron(EVOLUTIONS, weapon_levels).expect("invalid evolution recipes");
+        app.insert_resource(evolutions);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum EvolutionCondition {
+    /// A weapon of this kind is owned.
+    Weapon(WeaponKind),
+    Amount(u32),
+    Pierce(u32),
+    Bounce(u32),
+    Area(f32),
+    Duration(f32),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Recipe {
+    pub weapon: WeaponKind,
+    pub name: String,
+    /// Minimum weapon level.
+    pub level: u32,
+    #[serde(default)]
+    pub conditions: Vec<EvolutionCondition>,
+    #[serde(default)]
+    pub damage: i32,
+    #[serde(default)]
+    pub amount: u32,
+    /// Effects added to the evolved weapon.
+    #[serde(default)]
+    pub effects: Vec<(EffectTrigger, Effect)>,
+    /// Player stat modifiers granted while the evolved weapon is held.
+    #[serde(default)]
+    pub stats: Vec<Modifier>,
+}
+
+impl Recipe {
+    /// Whether `weapon` can evolve with this recipe while `owned` weapons are held.
+    /// Conditions check the weapon's own `stats`, without player bonuses.
+    pub fn available(&self, weapon: &Weapon, stats: &Stats, owned: &[WeaponKind]) -> bool {
+        weapon.kind == self.weapon
+            && weapon.level >= self.level
+            && self.conditions.iter().all(|condition| match *condition {
+                EvolutionCondition::Weapon(kind) => owned.contains(&kind),
+                EvolutionCondition::Amount(amount) => stats.get(Stat::Amount) >= amount as f32,
+                EvolutionCondition::Pierce(pierce) => stats.get(Stat::Pierce) >= pierce as f32,
+                EvolutionCondition::Bounce(bounce) => stats.get(Stat::Bounce) >= bounce as f32,
+                EvolutionCondition::Area(area) => stats.get(Stat::Area) >= area,
+                EvolutionCondition::Duration(duration) => stats.get(Stat::Duration) >= duration,
+            })
+    }
+}
+
+#[derive(Debug, Resource)]
+pub struct Evolutions {
+    pub recipes: Vec<Recipe>,
+}
+
+impl Evolutions {
+    /// Parses the recipes, rejecting any that needs a level above the weapon's max level.
+    pub fn from_ron(source: &str, weapon_levels: &WeaponLevels) -> Result<Self, String> {
+        let recipes: Vec<Recipe> = ron::from_str(source).map_err(|e| e.to_string())?;
+        for recipe in recipes.iter() {
+            let max_level = weapon_levels
+                .tables
+                .get(&recipe.weapon)
+                .map_or(1, |table| table.max_level());
+            if recipe.level > max_level {
+                return Err(format!(
+                    "{} needs {:?} level {}, but its max level is {max_level}",
+                    recipe.name, recipe.weapon, recipe.level
+                ));
+            }
+        }
+        Ok(Self { recipes })
+    }
+}
+
+/// Marks an evolved weapon. Each weapon evolves at most once.
+#[derive(Component)]
+pub struct Evolved {
+    pub name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weapon_levels() -> WeaponLevels {
+        WeaponLevels::from_ron(include_str!("../assets/weapon_levels.ron")).unwrap()
+    }
+
+    #[test]
+    fn evolutions_load() {
+        Evolutions::from_ron(EVOLUTIONS, &weapon_levels()).unwrap();
+    }
+
+    #[test]
+    fn unreachable_level_is_rejected() {
+        let recipes = r#"[(weapon: Gun, name: "Too Far", level: 99)]"#;
+        let error = Evolutions::from_ron(recipes, &weapon_levels()).unwrap_err();
+        assert!(error.contains("Too Far"), "{error}");
+    }
+}
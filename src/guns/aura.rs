@@ -0,0 +1,118 @@
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    collision::enemy_filter,
+    damage::{DamageSource, EnemyDamageEvent},
+    enemy::Enemy,
+    player::Player,
+    utils::remove_all_with,
+    GameState,
+};
+
+use super::{Weapon, WeaponFireEvent, WeaponKind};
+
+pub const AURA_DAMAGE: i32 = 3;
+pub const AURA_CRIT_CHANCE: f32 = 0.0;
+/// Time between damage ticks.
+pub const AURA_ATTACK_SPEED: f32 = 0.5;
+pub const AURA_RADIUS: f32 = 100.0;
+
+pub const AURA_COLOR: Color = Color::rgba(0.9, 0.6, 0.1, 0.2);
+
+pub struct AuraPlugin;
+
+impl Plugin for AuraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems((aura_spawn, aura_resize, aura_damage).in_set(OnUpdate(GameState::InGame)))
+            .add_system(remove_all_with::<AuraCircle>.in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(remove_all_with::<AuraCircle>.in_schedule(OnExit(GameState::GameOver)));
+    }
+}
+
+/// Visible circle of an aura, spawned as a child of the weapon.
+#[derive(Component)]
+pub struct AuraCircle;
+
+fn aura_spawn(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    weapons: Query<(Entity, &Weapon), Added<Weapon>>,
+) {
+    for (entity, weapon) in weapons.iter() {
+        if weapon.kind != WeaponKind::Aura {
+            continue;
+        }
+        commands.entity(entity).with_children(|builder| {
+            builder
+                .spawn(MaterialMesh2dBundle {
+                    mesh: meshes.add(shape::Circle::new(1.0).into()).into(),
+                    material: materials.add(ColorMaterial::from(AURA_COLOR)),
+                    // behind the player
+                    transform: Transform::from_xyz(0.0, 0.0, -0.5)
+                        .with_scale(Vec3::splat(weapon.area)),
+                    ..default()
+                })
+                .insert(AuraCircle);
+        });
+    }
+}
+
+fn aura_resize(
+    weapons: Query<&Weapon>,
+    mut circles: Query<(&Parent, &mut Transform), With<AuraCircle>>,
+) {
+    for (parent, mut transform) in circles.iter_mut() {
+        if let Ok(weapon) = weapons.get(parent.get()) {
+            transform.scale = Vec3::splat(weapon.area);
+        }
+    }
+}
+
+fn aura_damage(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    player: Query<&Transform, With<Player>>,
+    enemies: Query<(), With<Enemy>>,
+    mut weapons: Query<(Entity, &mut Weapon)>,
+    mut damage_event: EventWriter<EnemyDamageEvent>,
+    mut fire_event: EventWriter<WeaponFireEvent>,
+) {
+    let player_transform = player.single();
+
+    for (entity, mut weapon) in weapons.iter_mut() {
+        if weapon.kind != WeaponKind::Aura || !weapon.attack.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let source = DamageSource {
+            weapon: entity,
+            kind: weapon.kind,
+        };
+        rapier_context.intersections_with_shape(
+            player_transform.translation.truncate(),
+            0.0,
+            &Collider::ball(weapon.area),
+            enemy_filter(),
+            |e| {
+                if enemies.contains(e) {
+                    let (damage, crit) = weapon.roll_damage();
+                    damage_event.send(EnemyDamageEvent {
+                        target: e,
+                        damage,
+                        crit,
+                        source,
+                        from_effect: false,
+                    });
+                }
+                true
+            },
+        );
+
+        fire_event.send(WeaponFireEvent {
+            source,
+            projectiles: 1,
+        });
+    }
+}
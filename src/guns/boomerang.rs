@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+
+use crate::{player::Player, GameState};
+
+use super::{bullets_update, Bullet};
+
+pub const BOOMERANG_DAMAGE: i32 = 12;
+pub const BOOMERANG_CRIT_CHANCE: f32 = 0.1;
+/// Target range and the distance at which a boomerang turns back.
+pub const BOOMERANG_RANGE: f32 = 400.0;
+pub const BOOMERANG_ATTACK_SPEED: f32 = 1.5;
+pub const BOOMERANG_AMOUNT: u32 = 1;
+pub const BOOMERANG_SPREAD: f32 = 0.8;
+/// Boomerangs that never make it back disappear after this.
+pub const BOOMERANG_LIFETIME: f32 = 4.0;
+pub const BOOMERANG_VELOCITY: f32 = 700.0;
+/// Distance to the player at which a returning boomerang is caught.
+pub const BOOMERANG_CATCH_RADIUS: f32 = 25.0;
+
+pub struct BoomerangPlugin;
+
+impl Plugin for BoomerangPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            boomerang_update
+                .before(bullets_update)
+                .in_set(OnUpdate(GameState::InGame)),
+        );
+    }
+}
+
+/// Bullet flying out up to `max_distance`, then back to the player.
+/// Boomerangs pierce every enemy.
+#[derive(Component)]
+pub struct Boomerang {
+    max_distance: f32,
+    travelled: f32,
+    returning: bool,
+}
+
+impl Boomerang {
+    pub fn new(max_distance: f32) -> Self {
+        Self {
+            max_distance,
+            travelled: 0.0,
+            returning: false,
+        }
+    }
+}
+
+fn boomerang_update(
+    time: Res<Time>,
+    player: Query<&Transform, With<Player>>,
+    mut commands: Commands,
+    mut boomerangs: Query<(Entity, &Transform, &mut Bullet, &mut Boomerang)>,
+) {
+    let player_transform = player.single();
+
+    for (entity, transform, mut bullet, mut boomerang) in boomerangs.iter_mut() {
+        if !boomerang.returning {
+            boomerang.travelled += bullet.velocity.length() * time.delta_seconds();
+            if boomerang.travelled >= boomerang.max_distance {
+                boomerang.returning = true;
+                // enemies can be hit again on the way back
+                bullet.hit.clear();
+            }
+            continue;
+        }
+
+        let to_player = (player_transform.translation - transform.translation).truncate();
+        if to_player.length() < BOOMERANG_CATCH_RADIUS {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        bullet.velocity = to_player.normalize() * bullet.velocity.length();
+    }
+}
@@ -0,0 +1,170 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    collision::enemy_filter,
+    damage::{DamageSource, EnemyDamageEvent},
+    enemy::Enemy,
+    player::Player,
+    utils::remove_all_with,
+    GameState,
+};
+
+use super::{Targeting, Targets, Weapon, WeaponFireEvent, WeaponKind};
+
+/// Damage per tick.
+pub const LASER_DAMAGE: i32 = 4;
+pub const LASER_CRIT_CHANCE: f32 = 0.05;
+/// Length of the beam.
+pub const LASER_RANGE: f32 = 700.0;
+pub const LASER_ATTACK_SPEED: f32 = 3.0;
+pub const LASER_DURATION: f32 = 1.5;
+/// Time between damage ticks while the beam is active.
+pub const LASER_TICK: f32 = 0.2;
+
+pub const LASER_WIDTH: f32 = 6.0;
+pub const LASER_COLOR: Color = Color::rgba(1.0, 0.2, 0.2, 0.8);
+
+pub struct LaserPlugin;
+
+impl Plugin for LaserPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems((laser_fire, laser_update).in_set(OnUpdate(GameState::InGame)))
+            .add_system(remove_all_with::<LaserBeam>.in_schedule(OnExit(GameState::InGame)));
+    }
+}
+
+#[derive(Component)]
+pub struct LaserBeam {
+    direction: Vec2,
+    length: f32,
+    lifespan: Timer,
+    tick: Timer,
+    source: DamageSource,
+}
+
+fn laser_fire(
+    time: Res<Time>,
+    targets: Targets,
+    player: Query<&Transform, With<Player>>,
+    mut commands: Commands,
+    mut weapons: Query<(Entity, &mut Weapon, &Targeting)>,
+    mut fire_event: EventWriter<WeaponFireEvent>,
+) {
+    let player_transform = player.single();
+
+    for (entity, mut weapon, targeting) in weapons.iter_mut() {
+        if weapon.kind != WeaponKind::Laser || !weapon.attack.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let Some(target) = targets.find_aimed(*targeting, weapon.range) else {
+            continue;
+        };
+        let Some(direction) = (target.position - player_transform.translation)
+            .truncate()
+            .try_normalize()
+        else {
+            continue;
+        };
+
+        let source = DamageSource {
+            weapon: entity,
+            kind: weapon.kind,
+        };
+        commands
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: LASER_COLOR,
+                    custom_size: Some(Vec2::new(weapon.range, LASER_WIDTH)),
+                    ..default()
+                },
+                transform: beam_transform(player_transform.translation, direction, weapon.range),
+                ..default()
+            })
+            .insert(LaserBeam {
+                direction,
+                length: weapon.range,
+                lifespan: Timer::from_seconds(weapon.duration, TimerMode::Once),
+                tick: Timer::from_seconds(LASER_TICK, TimerMode::Repeating),
+                source,
+            });
+
+        fire_event.send(WeaponFireEvent {
+            source,
+            projectiles: 1,
+        });
+    }
+}
+
+/// Transform of a beam sprite starting at `origin`.
+fn beam_transform(origin: Vec3, direction: Vec2, length: f32) -> Transform {
+    let mut transform =
+        Transform::from_translation(origin + (direction * length / 2.0).extend(0.0))
+            .with_rotation(Quat::from_rotation_z(direction.y.atan2(direction.x)));
+    // above characters
+    transform.translation.z = 2.0;
+    transform
+}
+
+/// Entities laser beams follow or hit.
+#[derive(SystemParam)]
+struct LaserQueries<'w, 's> {
+    player: Query<'w, 's, &'static Transform, With<Player>>,
+    enemies: Query<'w, 's, (), With<Enemy>>,
+    weapons: Query<'w, 's, &'static Weapon>,
+}
+
+fn laser_update(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    queries: LaserQueries,
+    mut commands: Commands,
+    mut beams: Query<(Entity, &mut Transform, &mut LaserBeam), Without<Player>>,
+    mut damage_event: EventWriter<EnemyDamageEvent>,
+) {
+    let LaserQueries {
+        player,
+        enemies,
+        weapons,
+    } = queries;
+    let player_transform = player.single();
+
+    for (entity, mut transform, mut beam) in beams.iter_mut() {
+        let Ok(weapon) = weapons.get(beam.source.weapon) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        if beam.lifespan.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        // the beam follows the player
+        *transform = beam_transform(player_transform.translation, beam.direction, beam.length);
+
+        if !beam.tick.tick(time.delta()).just_finished() {
+            continue;
+        }
+        rapier_context.intersections_with_ray(
+            player_transform.translation.truncate(),
+            beam.direction,
+            beam.length,
+            true,
+            enemy_filter(),
+            |e, _| {
+                if enemies.contains(e) {
+                    let (damage, crit) = weapon.roll_damage();
+                    damage_event.send(EnemyDamageEvent {
+                        target: e,
+                        damage,
+                        crit,
+                        source: beam.source,
+                        from_effect: false,
+                    });
+                }
+                true
+            },
+        );
+    }
+}
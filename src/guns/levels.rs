@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::stats::{Modifier, ModifierKind, Stat};
+
+use super::WeaponKind;
+
+/// Level tables for every weapon kind, see `WeaponLevels::from_ron`.
+const WEAPON_LEVELS: &str = include_str!("../../assets/weapon_levels.ron");
+
+pub struct LevelsPlugin;
+
+impl Plugin for LevelsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(
+            WeaponLevels::from_ron(WEAPON_LEVELS).expect("invalid weapon level tables"),
+        );
+    }
+}
+
+/// Stat changes for reaching a level.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct LevelDelta {
+    pub damage: i32,
+    /// Seconds added to the attack cooldown.
+    pub cooldown: f32,
+    pub amount: u32,
+    pub area: f32,
+    /// Added to the projectile speed multiplier.
+    pub speed: f32,
+    pub pierce: u32,
+    pub bounce: u32,
+    pub duration: f32,
+    pub spread: f32,
+}
+
+impl LevelDelta {
+    /// Short description, e.g. "+5 damage, -0.1s cooldown".
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.damage != 0 {
+            parts.push(format!("{:+} damage", self.damage));
+        }
+        if self.cooldown != 0.0 {
+            parts.push(format!("{:+}s cooldown", self.cooldown));
+        }
+        if self.amount != 0 {
+            parts.push(format!("+{} amount", self.amount));
+        }
+        if self.area != 0.0 {
+            parts.push(format!("{:+} area", self.area));
+        }
+        if self.speed != 0.0 {
+            parts.push(format!("{:+}% speed", (self.speed * 100.0).round()));
+        }
+        if self.pierce != 0 {
+            parts.push(format!("+{} pierce", self.pierce));
+        }
+        if self.bounce != 0 {
+            parts.push(format!("+{} bounce", self.bounce));
+        }
+        if self.duration != 0.0 {
+            parts.push(format!("{:+}s duration", self.duration));
+        }
+        if self.spread != 0.0 {
+            parts.push(format!("{:+} spread", self.spread));
+        }
+        parts.join(", ")
+    }
+
+    /// Modifiers for the weapon's `Stats`, applied as `ModifierSource::Level`.
+    pub fn modifiers(&self) -> Vec<Modifier> {
+        [
+            (Stat::Damage, self.damage as f32),
+            (Stat::Cooldown, self.cooldown),
+            (Stat::Amount, self.amount as f32),
+            (Stat::Area, self.area),
+            (Stat::Speed, self.speed),
+            (Stat::Pierce, self.pierce as f32),
+            (Stat::Bounce, self.bounce as f32),
+            (Stat::Duration, self.duration),
+            (Stat::Spread, self.spread),
+        ]
+        .into_iter()
+        .filter(|(_, value)| *value != 0.0)
+        .map(|(stat, value)| Modifier {
+            stat,
+            kind: ModifierKind::Add(value),
+        })
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LevelTable {
+    /// Attack cooldown never goes below this.
+    pub min_cooldown: f32,
+    /// Changes for reaching level 2, 3 and so on.
+    pub levels: Vec<LevelDelta>,
+}
+
+impl LevelTable {
+    pub fn max_level(&self) -> u32 {
+        self.levels.len() as u32 + 1
+    }
+
+    /// Changes for going from `level` to the next one, `None` at max level.
+    pub fn next(&self, level: u32) -> Option<&LevelDelta> {
+        self.levels.get(level.checked_sub(1)? as usize)
+    }
+}
+
+#[derive(Debug, Resource)]
+pub struct WeaponLevels {
+    pub tables: HashMap<WeaponKind, LevelTable>,
+}
+
+impl WeaponLevels {
+    /// Parses the tables, requiring one for every weapon kind with a positive `min_cooldown`.
+    pub fn from_ron(source: &str) -> Result<Self, String> {
+        let tables: HashMap<WeaponKind, LevelTable> =
+            ron::from_str(source).map_err(|e| e.to_string())?;
+        for kind in WeaponKind::ALL {
+            let table = tables
+                .get(&kind)
+                .ok_or_else(|| format!("missing level table for {kind:?}"))?;
+            if table.min_cooldown <= 0.0 {
+                return Err(format!("{kind:?} min_cooldown must be positive"));
+            }
+        }
+        Ok(Self { tables })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tables for every weapon kind, `Gun` using `gun`.
+    fn tables_with_gun(gun: &str) -> String {
+        let tables = WeaponKind::ALL
+            .iter()
+            .map(|kind| match kind {
+                WeaponKind::Gun => format!("Gun: {gun},"),
+                _ => format!("{kind:?}: (min_cooldown: 0.1, levels: []),"),
+            })
+            .collect::<String>();
+        format!("{{{tables}}}")
+    }
+
+    #[test]
+    fn weapon_levels_load() {
+        WeaponLevels::from_ron(WEAPON_LEVELS).unwrap();
+    }
+
+    #[test]
+    fn next_walks_the_level_table() {
+        let levels = WeaponLevels::from_ron(&tables_with_gun(
+            "(min_cooldown: 0.1, levels: [(damage: 5), (amount: 1, cooldown: -0.2)])",
+        ))
+        .unwrap();
+        let table = &levels.tables[&WeaponKind::Gun];
+        assert_eq!(table.max_level(), 3);
+        assert_eq!(table.next(1).unwrap().damage, 5);
+        assert_eq!(table.next(2).unwrap().amount, 1);
+        assert!(table.next(3).is_none());
+        assert!(table.next(0).is_none());
+
+        // only changed values become modifiers
+        let stats = table
+            .next(2)
+            .unwrap()
+            .modifiers()
+            .iter()
+            .map(|modifier| modifier.stat)
+            .collect::<Vec<_>>();
+        assert_eq!(stats, [Stat::Cooldown, Stat::Amount]);
+    }
+
+    #[test]
+    fn bad_tables_are_rejected() {
+        let missing = WeaponLevels::from_ron("{ Gun: (min_cooldown: 0.1, levels: []) }");
+        assert!(missing.unwrap_err().contains("missing"));
+        let zero_cooldown =
+            WeaponLevels::from_ron(&tables_with_gun("(min_cooldown: 0.0, levels: [])"));
+        assert!(zero_cooldown.unwrap_err().contains("min_cooldown"));
+    }
+}
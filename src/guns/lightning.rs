@@ -0,0 +1,170 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    collision::enemy_filter,
+    damage::{DamageSource, EnemyDamageEvent},
+    enemy::Enemy,
+    player::Player,
+    utils::remove_all_with,
+    GameState,
+};
+
+use super::{nearest_enemy, Targeting, Targets, Weapon, WeaponFireEvent, WeaponKind};
+
+pub const LIGHTNING_DAMAGE: i32 = 15;
+pub const LIGHTNING_CRIT_CHANCE: f32 = 0.1;
+pub const LIGHTNING_RANGE: f32 = 600.0;
+pub const LIGHTNING_ATTACK_SPEED: f32 = 1.5;
+/// Number of jumps after the first strike.
+pub const LIGHTNING_JUMPS: u32 = 3;
+pub const LIGHTNING_JUMP_RADIUS: f32 = 250.0;
+/// Damage multiplier applied on every jump.
+pub const LIGHTNING_FALLOFF: f32 = 0.7;
+
+pub const LIGHTNING_BOLT_TIME: f32 = 0.15;
+pub const LIGHTNING_BOLT_WIDTH: f32 = 4.0;
+pub const LIGHTNING_BOLT_COLOR: Color = Color::rgb(0.6, 0.8, 1.0);
+
+pub struct LightningPlugin;
+
+impl Plugin for LightningPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (lightning_strike, lightning_bolt_update).in_set(OnUpdate(GameState::InGame)),
+        )
+        .add_system(remove_all_with::<LightningBolt>.in_schedule(OnExit(GameState::InGame)));
+    }
+}
+
+/// Line drawn between two struck positions.
+#[derive(Component)]
+pub struct LightningBolt {
+    lifespan: Timer,
+}
+
+fn spawn_bolt(commands: &mut Commands, from: Vec3, to: Vec3) {
+    let segment = (to - from).truncate();
+    let mut transform = Transform::from_translation((from + to) / 2.0)
+        .with_rotation(Quat::from_rotation_z(segment.y.atan2(segment.x)));
+    // above characters
+    transform.translation.z = 2.0;
+
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: LIGHTNING_BOLT_COLOR,
+                custom_size: Some(Vec2::new(segment.length(), LIGHTNING_BOLT_WIDTH)),
+                ..default()
+            },
+            transform,
+            ..default()
+        })
+        .insert(LightningBolt {
+            lifespan: Timer::from_seconds(LIGHTNING_BOLT_TIME, TimerMode::Once),
+        });
+}
+
+/// Entities lightning strikes from or jumps between.
+#[derive(SystemParam)]
+struct LightningQueries<'w, 's> {
+    player: Query<'w, 's, &'static Transform, With<Player>>,
+    enemies: Query<'w, 's, &'static Transform, With<Enemy>>,
+    weapons: Query<'w, 's, (Entity, &'static mut Weapon, &'static Targeting)>,
+}
+
+fn lightning_strike(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    targets: Targets,
+    queries: LightningQueries,
+    mut commands: Commands,
+    mut damage_event: EventWriter<EnemyDamageEvent>,
+    mut fire_event: EventWriter<WeaponFireEvent>,
+) {
+    let LightningQueries {
+        player,
+        enemies,
+        mut weapons,
+    } = queries;
+    let player_transform = player.single();
+
+    for (entity, mut weapon, targeting) in weapons.iter_mut() {
+        if weapon.kind != WeaponKind::Lightning || !weapon.attack.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let Some(aimed) = targets.find_aimed(*targeting, weapon.range) else {
+            continue;
+        };
+        // manual aim only gives a direction, strike the first enemy along it
+        let struck = aimed.entity.or_else(|| {
+            let origin = player_transform.translation.truncate();
+            let direction = (aimed.position.truncate() - origin).try_normalize()?;
+            rapier_context
+                .cast_ray(origin, direction, weapon.range, true, enemy_filter())
+                .map(|(e, _)| e)
+        });
+        // lightning needs an enemy to strike
+        let Some((mut target, mut to)) = struck.and_then(|e| {
+            enemies
+                .get(e)
+                .ok()
+                .map(|transform| (e, transform.translation))
+        }) else {
+            continue;
+        };
+
+        let source = DamageSource {
+            weapon: entity,
+            kind: weapon.kind,
+        };
+        let (damage, crit) = weapon.roll_damage();
+        let mut damage = damage as f32;
+        let mut from = player_transform.translation;
+        let mut hit = vec![target];
+        loop {
+            damage_event.send(EnemyDamageEvent {
+                target,
+                damage: damage.round() as i32,
+                crit,
+                source,
+                from_effect: false,
+            });
+            spawn_bolt(&mut commands, from, to);
+
+            if hit.len() > weapon.amount as usize {
+                break;
+            }
+            let Some((next, position)) =
+                nearest_enemy(&rapier_context, &enemies, to, weapon.area, |e| {
+                    hit.contains(&e)
+                })
+            else {
+                break;
+            };
+            hit.push(next);
+            target = next;
+            from = to;
+            to = position;
+            damage *= LIGHTNING_FALLOFF;
+        }
+
+        fire_event.send(WeaponFireEvent {
+            source,
+            projectiles: 1,
+        });
+    }
+}
+
+fn lightning_bolt_update(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut bolts: Query<(Entity, &mut LightningBolt)>,
+) {
+    for (entity, mut bolt) in bolts.iter_mut() {
+        if bolt.lifespan.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    collision::enemy_filter,
+    damage::{DamageSource, EnemyDamageEvent},
+    enemy::Enemy,
+    player::Player,
+    utils::remove_all_with,
+    GameState,
+};
+
+use super::{Weapon, WeaponFireEvent, WeaponKind};
+
+pub const MINE_DAMAGE: i32 = 20;
+pub const MINE_CRIT_CHANCE: f32 = 0.1;
+pub const MINE_ATTACK_SPEED: f32 = 1.5;
+pub const MINE_AMOUNT: u32 = 1;
+pub const MINE_EXPLOSION_RADIUS: f32 = 70.0;
+/// Time before an untouched mine disappears.
+pub const MINE_LIFETIME: f32 = 8.0;
+
+/// Distance at which an enemy sets a mine off.
+pub const MINE_TRIGGER_RADIUS: f32 = 12.0;
+/// Distance between mines dropped in one go.
+pub const MINE_SCATTER: f32 = 40.0;
+pub const MINE_SIZE: f32 = 6.0;
+pub const MINE_COLOR: Color = Color::rgb(0.9, 0.1, 0.1);
+
+pub struct MinePlugin;
+
+impl Plugin for MinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MineAssets>()
+            .add_systems((mine_spawn, mine_update).in_set(OnUpdate(GameState::InGame)))
+            .add_system(remove_all_with::<Mine>.in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(remove_all_with::<Mine>.in_schedule(OnExit(GameState::GameOver)));
+    }
+}
+
+/// Mesh and material shared by all mines.
+#[derive(Resource)]
+pub struct MineAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+impl FromWorld for MineAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(shape::Circle::new(MINE_SIZE).into());
+        let material = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(ColorMaterial::from(MINE_COLOR));
+        Self { mesh, material }
+    }
+}
+
+#[derive(Component)]
+pub struct Mine {
+    lifespan: Timer,
+    explosion_radius: f32,
+    source: DamageSource,
+}
+
+fn mine_spawn(
+    time: Res<Time>,
+    player: Query<&Transform, With<Player>>,
+    mine_assets: Res<MineAssets>,
+    mut commands: Commands,
+    mut weapons: Query<(Entity, &mut Weapon)>,
+    mut fire_event: EventWriter<WeaponFireEvent>,
+) {
+    let player_transform = player.single();
+
+    for (entity, mut weapon) in weapons.iter_mut() {
+        if weapon.kind != WeaponKind::Mine || !weapon.attack.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let source = DamageSource {
+            weapon: entity,
+            kind: weapon.kind,
+        };
+        for n in 0..weapon.amount {
+            // first mine under the player, the rest around it
+            let offset = if n == 0 {
+                Vec2::ZERO
+            } else {
+                Vec2::from_angle(rand::random::<f32>() * std::f32::consts::TAU) * MINE_SCATTER
+            };
+            let mut translation = player_transform.translation + offset.extend(0.0);
+            // under characters
+            translation.z = -0.5;
+
+            commands
+                .spawn(MaterialMesh2dBundle {
+                    mesh: mine_assets.mesh.clone().into(),
+                    material: mine_assets.material.clone(),
+                    transform: Transform::from_translation(translation),
+                    ..default()
+                })
+                .insert(Mine {
+                    lifespan: Timer::from_seconds(weapon.duration, TimerMode::Once),
+                    explosion_radius: weapon.area,
+                    source,
+                });
+        }
+
+        fire_event.send(WeaponFireEvent {
+            source,
+            projectiles: weapon.amount,
+        });
+    }
+}
+
+fn mine_update(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    enemies: Query<(), With<Enemy>>,
+    weapons: Query<&Weapon>,
+    mut commands: Commands,
+    mut mines: Query<(Entity, &Transform, &mut Mine)>,
+    mut damage_event: EventWriter<EnemyDamageEvent>,
+) {
+    for (entity, transform, mut mine) in mines.iter_mut() {
+        let Ok(weapon) = weapons.get(mine.source.weapon) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        if mine.lifespan.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let mut triggered = false;
+        rapier_context.intersections_with_shape(
+            transform.translation.truncate(),
+            0.0,
+            &Collider::ball(MINE_TRIGGER_RADIUS),
+            enemy_filter(),
+            |e| {
+                triggered = enemies.contains(e);
+                !triggered
+            },
+        );
+        if !triggered {
+            continue;
+        }
+
+        rapier_context.intersections_with_shape(
+            transform.translation.truncate(),
+            0.0,
+            &Collider::ball(mine.explosion_radius),
+            enemy_filter(),
+            |e| {
+                if enemies.contains(e) {
+                    let (damage, crit) = weapon.roll_damage();
+                    damage_event.send(EnemyDamageEvent {
+                        target: e,
+                        damage,
+                        crit,
+                        source: mine.source,
+                        from_effect: false,
+                    });
+                }
+                true
+            },
+        );
+        commands.entity(entity).despawn();
+    }
+}
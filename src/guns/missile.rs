@@ -0,0 +1,190 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    collision::enemy_filter,
+    damage::{DamageSource, EnemyDamageEvent},
+    enemy::Enemy,
+    player::Player,
+    utils::remove_all_with,
+    GameAssets, GameState,
+};
+
+use super::{nearest_enemy, Targeting, Targets, Weapon, WeaponFireEvent, WeaponKind};
+
+pub const MISSILE_DAMAGE: i32 = 12;
+pub const MISSILE_CRIT_CHANCE: f32 = 0.1;
+pub const MISSILE_RANGE: f32 = 800.0;
+pub const MISSILE_ATTACK_SPEED: f32 = 2.0;
+pub const MISSILE_AMOUNT: u32 = 1;
+pub const MISSILE_EXPLOSION_RADIUS: f32 = 80.0;
+pub const MISSILE_LIFETIME: f32 = 4.0;
+
+pub const MISSILE_VELOCITY: f32 = 600.0;
+/// Radians per second.
+pub const MISSILE_TURN_RATE: f32 = 4.0;
+/// Angle between missiles of one volley when they launch.
+pub const MISSILE_LAUNCH_SPREAD: f32 = 0.4;
+pub const MISSILE_HIT_RADIUS: f32 = 10.0;
+
+pub struct MissilePlugin;
+
+impl Plugin for MissilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems((missile_spawn, missile_update).in_set(OnUpdate(GameState::InGame)))
+            .add_system(remove_all_with::<Missile>.in_schedule(OnExit(GameState::InGame)));
+    }
+}
+
+#[derive(Component)]
+pub struct Missile {
+    target: Option<Entity>,
+    direction: Vec2,
+    lifespan: Timer,
+    damage: i32,
+    crit: bool,
+    explosion_radius: f32,
+    speed: f32,
+    source: DamageSource,
+}
+
+fn missile_spawn(
+    time: Res<Time>,
+    game_assets: Res<GameAssets>,
+    targets: Targets,
+    player: Query<&Transform, With<Player>>,
+    mut commands: Commands,
+    mut weapons: Query<(Entity, &mut Weapon, &Targeting)>,
+    mut fire_event: EventWriter<WeaponFireEvent>,
+) {
+    let player_transform = player.single();
+
+    for (entity, mut weapon, targeting) in weapons.iter_mut() {
+        if weapon.kind != WeaponKind::Missile || !weapon.attack.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let Some(target) = targets.find_aimed(*targeting, weapon.range) else {
+            continue;
+        };
+        let Some(direction) = (target.position - player_transform.translation)
+            .truncate()
+            .try_normalize()
+        else {
+            continue;
+        };
+
+        let source = DamageSource {
+            weapon: entity,
+            kind: weapon.kind,
+        };
+        for n in 0..weapon.amount {
+            let offset = (n as f32 - (weapon.amount - 1) as f32 / 2.0) * MISSILE_LAUNCH_SPREAD;
+            let direction = Vec2::from_angle(offset).rotate(direction);
+            let (damage, crit) = weapon.roll_damage();
+
+            commands
+                .spawn(SpriteBundle {
+                    transform: Transform::from_translation(player_transform.translation)
+                        .with_rotation(Quat::from_rotation_z(direction.y.atan2(direction.x))),
+                    texture: game_assets.bullet.clone(),
+                    ..default()
+                })
+                .insert(Missile {
+                    target: target.entity,
+                    direction,
+                    lifespan: Timer::from_seconds(weapon.duration, TimerMode::Once),
+                    damage,
+                    crit,
+                    explosion_radius: weapon.area,
+                    speed: MISSILE_VELOCITY * weapon.speed,
+                    source,
+                });
+        }
+
+        fire_event.send(WeaponFireEvent {
+            source,
+            projectiles: weapon.amount,
+        });
+    }
+}
+
+fn missile_update(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    enemies: Query<&Transform, With<Enemy>>,
+    mut commands: Commands,
+    mut missiles: Query<(Entity, &mut Transform, &mut Missile), Without<Enemy>>,
+    mut damage_event: EventWriter<EnemyDamageEvent>,
+) {
+    for (entity, mut transform, mut missile) in missiles.iter_mut() {
+        if missile.lifespan.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        // retarget when the target died mid-flight
+        let target = match missile.target.and_then(|target| enemies.get(target).ok()) {
+            Some(target) => Some(target.translation),
+            None => {
+                let nearest = nearest_enemy(
+                    &rapier_context,
+                    &enemies,
+                    transform.translation,
+                    MISSILE_RANGE,
+                    |_| false,
+                );
+                missile.target = nearest.map(|(target, _)| target);
+                nearest.map(|(_, position)| position)
+            }
+        };
+
+        let desired = target.map(|target| (target - transform.translation).truncate());
+        if let Some(desired) = desired.filter(|desired| *desired != Vec2::ZERO) {
+            let max_turn = MISSILE_TURN_RATE * time.delta_seconds();
+            let turn = missile
+                .direction
+                .angle_between(desired)
+                .clamp(-max_turn, max_turn);
+            missile.direction = Vec2::from_angle(turn).rotate(missile.direction);
+        }
+        transform.translation +=
+            (missile.direction * missile.speed * time.delta_seconds()).extend(0.0);
+        transform.rotation = Quat::from_rotation_z(missile.direction.y.atan2(missile.direction.x));
+
+        let mut impact = false;
+        rapier_context.intersections_with_shape(
+            transform.translation.truncate(),
+            0.0,
+            &Collider::ball(MISSILE_HIT_RADIUS),
+            enemy_filter(),
+            |e| {
+                impact = enemies.contains(e);
+                !impact
+            },
+        );
+        if !impact {
+            continue;
+        }
+
+        rapier_context.intersections_with_shape(
+            transform.translation.truncate(),
+            0.0,
+            &Collider::ball(missile.explosion_radius),
+            enemy_filter(),
+            |e| {
+                if enemies.contains(e) {
+                    damage_event.send(EnemyDamageEvent {
+                        target: e,
+                        damage: missile.damage,
+                        crit: missile.crit,
+                        source: missile.source,
+                        from_effect: false,
+                    });
+                }
+                true
+            },
+        );
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,818 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{prelude::*, utils::HashSet};
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    collision::enemy_filter,
+    damage::{DamageSource, EnemyDamageEvent},
+    effects::Effects,
+    enemy::Enemy,
+    patterns::{Emitter, Team},
+    player::Player,
+    stats::{Stat, Stats},
+    utils::remove_all_with,
+    GameAssets, GameState,
+};
+
+mod aura;
+mod boomerang;
+mod laser;
+mod levels;
+mod lightning;
+mod mine;
+mod missile;
+mod nova;
+mod orbit;
+mod spiral;
+mod targeting;
+mod whip;
+mod zone;
+
+use aura::{AURA_ATTACK_SPEED, AURA_CRIT_CHANCE, AURA_DAMAGE, AURA_RADIUS};
+use boomerang::{
+    Boomerang, BOOMERANG_AMOUNT, BOOMERANG_ATTACK_SPEED, BOOMERANG_CRIT_CHANCE, BOOMERANG_DAMAGE,
+    BOOMERANG_LIFETIME, BOOMERANG_RANGE, BOOMERANG_SPREAD, BOOMERANG_VELOCITY,
+};
+use laser::{LASER_ATTACK_SPEED, LASER_CRIT_CHANCE, LASER_DAMAGE, LASER_DURATION, LASER_RANGE};
+pub use levels::WeaponLevels;
+use lightning::{
+    LIGHTNING_ATTACK_SPEED, LIGHTNING_CRIT_CHANCE, LIGHTNING_DAMAGE, LIGHTNING_JUMPS,
+    LIGHTNING_JUMP_RADIUS, LIGHTNING_RANGE,
+};
+use mine::{
+    MINE_AMOUNT, MINE_ATTACK_SPEED, MINE_CRIT_CHANCE, MINE_DAMAGE, MINE_EXPLOSION_RADIUS,
+    MINE_LIFETIME,
+};
+use missile::{
+    MISSILE_AMOUNT, MISSILE_ATTACK_SPEED, MISSILE_CRIT_CHANCE, MISSILE_DAMAGE,
+    MISSILE_EXPLOSION_RADIUS, MISSILE_LIFETIME, MISSILE_RANGE,
+};
+use nova::{NOVA_ATTACK_SPEED, NOVA_CRIT_CHANCE, NOVA_DAMAGE, NOVA_LIFETIME, NOVA_PATTERN};
+use orbit::{
+    ORBIT_ATTACK_SPEED, ORBIT_CRIT_CHANCE, ORBIT_DAMAGE, ORBIT_DURATION, ORBIT_PROJECTILES,
+    ORBIT_RADIUS,
+};
+use spiral::{
+    SPIRAL_ATTACK_SPEED, SPIRAL_CRIT_CHANCE, SPIRAL_DAMAGE, SPIRAL_LIFETIME, SPIRAL_PATTERN,
+    SPIRAL_RANGE,
+};
+pub use targeting::{Target, Targeting, Targets};
+use whip::{WHIP_AMOUNT, WHIP_ATTACK_SPEED, WHIP_CRIT_CHANCE, WHIP_DAMAGE, WHIP_REACH};
+use zone::{
+    ZONE_AMOUNT, ZONE_ATTACK_SPEED, ZONE_CRIT_CHANCE, ZONE_DAMAGE, ZONE_DURATION, ZONE_RADIUS,
+    ZONE_RANGE,
+};
+
+pub const BULLET_LIFETIME: f32 = 1.0;
+pub const BULLET_VELOCITY: f32 = 2000.0;
+pub const BULLET_RADIUS: f32 = 2.5;
+/// Range in which a bouncing bullet looks for its next target.
+pub const BULLET_BOUNCE_RANGE: f32 = 400.0;
+
+pub const CRIT_DAMAGE_MULTIPLIER: i32 = 2;
+
+/// Time between repeated shots of a volley with fewer targets than projectiles.
+pub const VOLLEY_INTERVAL: f32 = 0.1;
+
+pub const MAX_WEAPONS: usize = 6;
+
+pub const GUN_DAMAGE: i32 = 10;
+pub const GUN_CRIT_CHANCE: f32 = 0.1;
+pub const GUN_RANGE: f32 = 900.0;
+pub const GUN_ATTACK_SPEED: f32 = 0.5;
+
+pub const SHOTGUN_DAMAGE: i32 = 6;
+pub const SHOTGUN_CRIT_CHANCE: f32 = 0.05;
+pub const SHOTGUN_RANGE: f32 = 500.0;
+pub const SHOTGUN_ATTACK_SPEED: f32 = 1.2;
+pub const SHOTGUN_PROJECTILES: u32 = 5;
+/// Full width of the cone in radians.
+pub const SHOTGUN_SPREAD: f32 = 0.6;
+
+/// Patterns fired by weapons, they must exist in `Patterns`.
+pub const WEAPON_PATTERNS: [&str; 2] = [SPIRAL_PATTERN, NOVA_PATTERN];
+
+/// Shortest attack cooldown in seconds, whatever the stats.
+pub const MIN_ATTACK_COOLDOWN: f32 = 0.05;
+
+pub struct GunsPlugin;
+
+impl Plugin for GunsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShootEvent>()
+            .add_event::<WeaponFireEvent>()
+            .add_systems(
+                (
+                    weapon_stats_update,
+                    player_shoot,
+                    bullets_spawn,
+                    bullets_update,
+                )
+                    .in_set(OnUpdate(GameState::InGame)),
+            )
+            .add_system(remove_all_with::<BulletMarker>.in_schedule(OnExit(GameState::InGame)))
+            .add_system(remove_all_with::<WeaponMarker>.in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(remove_all_with::<WeaponMarker>.in_schedule(OnExit(GameState::GameOver)))
+            .add_plugin(aura::AuraPlugin)
+            .add_plugin(boomerang::BoomerangPlugin)
+            .add_plugin(laser::LaserPlugin)
+            .add_plugin(levels::LevelsPlugin)
+            .add_plugin(lightning::LightningPlugin)
+            .add_plugin(mine::MinePlugin)
+            .add_plugin(missile::MissilePlugin)
+            .add_plugin(orbit::OrbitPlugin)
+            .add_plugin(targeting::TargetingPlugin)
+            .add_plugin(whip::WhipPlugin)
+            .add_plugin(zone::ZonePlugin);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum WeaponKind {
+    Gun,
+    Shotgun,
+    Orbit,
+    Aura,
+    Lightning,
+    Missile,
+    Laser,
+    Mine,
+    Zone,
+    Whip,
+    Boomerang,
+    Spiral,
+    Nova,
+}
+
+impl WeaponKind {
+    pub const ALL: [WeaponKind; 13] = [
+        WeaponKind::Gun,
+        WeaponKind::Shotgun,
+        WeaponKind::Orbit,
+        WeaponKind::Aura,
+        WeaponKind::Lightning,
+        WeaponKind::Missile,
+        WeaponKind::Laser,
+        WeaponKind::Mine,
+        WeaponKind::Zone,
+        WeaponKind::Whip,
+        WeaponKind::Boomerang,
+        WeaponKind::Spiral,
+        WeaponKind::Nova,
+    ];
+
+    /// Weapons firing `Bullet`s.
+    pub fn is_projectile(self) -> bool {
+        matches!(
+            self,
+            WeaponKind::Gun | WeaponKind::Shotgun | WeaponKind::Boomerang
+        )
+    }
+}
+
+/// A weapon slot. Weapons are spawned as children of the player.
+/// Values are recomputed from the weapon's and the player's `Stats`
+/// by `weapon_stats_update`, change those instead.
+#[derive(Component)]
+pub struct Weapon {
+    pub kind: WeaponKind,
+    pub level: u32,
+    pub damage: i32,
+    pub crit_chance: f32,
+    pub range: f32,
+    pub attack: Timer,
+    /// Number of projectiles per shot.
+    pub amount: u32,
+    /// Number of enemies a projectile passes through.
+    pub pierce: u32,
+    /// Number of times a projectile redirects to the next nearest enemy.
+    pub bounce: u32,
+    /// Size of the weapon's effect in pixels, e.g. orbit or aura radius.
+    pub area: f32,
+    /// How long spawned projectiles or effects last in seconds.
+    pub duration: f32,
+    /// Multiplier for projectile speed.
+    pub speed: f32,
+}
+
+impl Weapon {
+    /// Damage of a single hit, rolling for a crit.
+    pub fn roll_damage(&self) -> (i32, bool) {
+        let crit = rand::random::<f32>() < self.crit_chance;
+        if crit {
+            (self.damage * CRIT_DAMAGE_MULTIPLIER, true)
+        } else {
+            (self.damage, false)
+        }
+    }
+}
+
+/// Shots of the current volley waiting to be fired at already targeted enemies.
+#[derive(Component)]
+pub struct Volley {
+    queue: VecDeque<Target>,
+    timer: Timer,
+}
+
+impl Default for Volley {
+    fn default() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            timer: Timer::from_seconds(VOLLEY_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Spreads the projectiles of a shot evenly over a cone toward the target.
+#[derive(Component)]
+pub struct Spread {
+    /// Full width of the cone in radians.
+    pub angle: f32,
+}
+
+#[derive(Component)]
+pub struct WeaponMarker;
+
+#[derive(Bundle)]
+pub struct WeaponBundle {
+    #[bundle]
+    spatial: SpatialBundle,
+    weapon: Weapon,
+    stats: Stats,
+    targeting: Targeting,
+    volley: Volley,
+    effects: Effects,
+    marker: WeaponMarker,
+}
+
+impl WeaponBundle {
+    pub fn new(kind: WeaponKind) -> Self {
+        let weapon = match kind {
+            WeaponKind::Gun => Weapon {
+                kind,
+                level: 1,
+                damage: GUN_DAMAGE,
+                crit_chance: GUN_CRIT_CHANCE,
+                range: GUN_RANGE,
+                attack: Timer::from_seconds(GUN_ATTACK_SPEED, TimerMode::Repeating),
+                amount: 1,
+                pierce: 0,
+                bounce: 0,
+                area: 0.0,
+                duration: 0.0,
+                speed: 1.0,
+            },
+            WeaponKind::Shotgun => Weapon {
+                kind,
+                level: 1,
+                damage: SHOTGUN_DAMAGE,
+                crit_chance: SHOTGUN_CRIT_CHANCE,
+                range: SHOTGUN_RANGE,
+                attack: Timer::from_seconds(SHOTGUN_ATTACK_SPEED, TimerMode::Repeating),
+                amount: SHOTGUN_PROJECTILES,
+                pierce: 0,
+                bounce: 0,
+                area: 0.0,
+                duration: 0.0,
+                speed: 1.0,
+            },
+            WeaponKind::Orbit => Weapon {
+                kind,
+                level: 1,
+                damage: ORBIT_DAMAGE,
+                crit_chance: ORBIT_CRIT_CHANCE,
+                range: 0.0,
+                attack: Timer::from_seconds(ORBIT_ATTACK_SPEED, TimerMode::Repeating),
+                amount: ORBIT_PROJECTILES,
+                pierce: 0,
+                bounce: 0,
+                area: ORBIT_RADIUS,
+                duration: ORBIT_DURATION,
+                speed: 1.0,
+            },
+            WeaponKind::Aura => Weapon {
+                kind,
+                level: 1,
+                damage: AURA_DAMAGE,
+                crit_chance: AURA_CRIT_CHANCE,
+                range: 0.0,
+                attack: Timer::from_seconds(AURA_ATTACK_SPEED, TimerMode::Repeating),
+                amount: 1,
+                pierce: 0,
+                bounce: 0,
+                area: AURA_RADIUS,
+                duration: 0.0,
+                speed: 1.0,
+            },
+            WeaponKind::Lightning => Weapon {
+                kind,
+                level: 1,
+                damage: LIGHTNING_DAMAGE,
+                crit_chance: LIGHTNING_CRIT_CHANCE,
+                range: LIGHTNING_RANGE,
+                attack: Timer::from_seconds(LIGHTNING_ATTACK_SPEED, TimerMode::Repeating),
+                amount: LIGHTNING_JUMPS,
+                pierce: 0,
+                bounce: 0,
+                area: LIGHTNING_JUMP_RADIUS,
+                duration: 0.0,
+                speed: 1.0,
+            },
+            WeaponKind::Missile => Weapon {
+                kind,
+                level: 1,
+                damage: MISSILE_DAMAGE,
+                crit_chance: MISSILE_CRIT_CHANCE,
+                range: MISSILE_RANGE,
+                attack: Timer::from_seconds(MISSILE_ATTACK_SPEED, TimerMode::Repeating),
+                amount: MISSILE_AMOUNT,
+                pierce: 0,
+                bounce: 0,
+                area: MISSILE_EXPLOSION_RADIUS,
+                duration: MISSILE_LIFETIME,
+                speed: 1.0,
+            },
+            WeaponKind::Laser => Weapon {
+                kind,
+                level: 1,
+                damage: LASER_DAMAGE,
+                crit_chance: LASER_CRIT_CHANCE,
+                range: LASER_RANGE,
+                attack: Timer::from_seconds(LASER_ATTACK_SPEED, TimerMode::Repeating),
+                amount: 1,
+                pierce: 0,
+                bounce: 0,
+                area: 0.0,
+                duration: LASER_DURATION,
+                speed: 1.0,
+            },
+            WeaponKind::Mine => Weapon {
+                kind,
+                level: 1,
+                damage: MINE_DAMAGE,
+                crit_chance: MINE_CRIT_CHANCE,
+                range: 0.0,
+                attack: Timer::from_seconds(MINE_ATTACK_SPEED, TimerMode::Repeating),
+                amount: MINE_AMOUNT,
+                pierce: 0,
+                bounce: 0,
+                area: MINE_EXPLOSION_RADIUS,
+                duration: MINE_LIFETIME,
+                speed: 1.0,
+            },
+            WeaponKind::Zone => Weapon {
+                kind,
+                level: 1,
+                damage: ZONE_DAMAGE,
+                crit_chance: ZONE_CRIT_CHANCE,
+                range: ZONE_RANGE,
+                attack: Timer::from_seconds(ZONE_ATTACK_SPEED, TimerMode::Repeating),
+                amount: ZONE_AMOUNT,
+                pierce: 0,
+                bounce: 0,
+                area: ZONE_RADIUS,
+                duration: ZONE_DURATION,
+                speed: 1.0,
+            },
+            WeaponKind::Whip => Weapon {
+                kind,
+                level: 1,
+                damage: WHIP_DAMAGE,
+                crit_chance: WHIP_CRIT_CHANCE,
+                range: 0.0,
+                attack: Timer::from_seconds(WHIP_ATTACK_SPEED, TimerMode::Repeating),
+                amount: WHIP_AMOUNT,
+                pierce: 0,
+                bounce: 0,
+                area: WHIP_REACH,
+                duration: 0.0,
+                speed: 1.0,
+            },
+            WeaponKind::Boomerang => Weapon {
+                kind,
+                level: 1,
+                damage: BOOMERANG_DAMAGE,
+                crit_chance: BOOMERANG_CRIT_CHANCE,
+                range: BOOMERANG_RANGE,
+                attack: Timer::from_seconds(BOOMERANG_ATTACK_SPEED, TimerMode::Repeating),
+                amount: BOOMERANG_AMOUNT,
+                pierce: 0,
+                bounce: 0,
+                area: 0.0,
+                duration: BOOMERANG_LIFETIME,
+                speed: 1.0,
+            },
+            WeaponKind::Spiral => Weapon {
+                kind,
+                level: 1,
+                damage: SPIRAL_DAMAGE,
+                crit_chance: SPIRAL_CRIT_CHANCE,
+                range: SPIRAL_RANGE,
+                attack: Timer::from_seconds(SPIRAL_ATTACK_SPEED, TimerMode::Repeating),
+                amount: 1,
+                pierce: 0,
+                bounce: 0,
+                area: 0.0,
+                duration: SPIRAL_LIFETIME,
+                speed: 1.0,
+            },
+            WeaponKind::Nova => Weapon {
+                kind,
+                level: 1,
+                damage: NOVA_DAMAGE,
+                crit_chance: NOVA_CRIT_CHANCE,
+                range: 0.0,
+                attack: Timer::from_seconds(NOVA_ATTACK_SPEED, TimerMode::Repeating),
+                amount: 1,
+                pierce: 0,
+                bounce: 0,
+                area: 0.0,
+                duration: NOVA_LIFETIME,
+                speed: 1.0,
+            },
+        };
+        let stats = Stats::new([
+            (Stat::Damage, weapon.damage as f32),
+            (Stat::CritChance, weapon.crit_chance),
+            (Stat::Cooldown, weapon.attack.duration().as_secs_f32()),
+            (Stat::Amount, weapon.amount as f32),
+            (Stat::Pierce, weapon.pierce as f32),
+            (Stat::Bounce, weapon.bounce as f32),
+            (Stat::Area, weapon.area),
+            (Stat::Duration, weapon.duration),
+            (Stat::Speed, weapon.speed),
+            (Stat::Spread, base_spread(kind).unwrap_or_default()),
+        ]);
+        Self {
+            spatial: SpatialBundle::default(),
+            weapon,
+            stats,
+            targeting: Targeting::default_for(kind),
+            volley: Volley::default(),
+            effects: Effects::default(),
+            marker: WeaponMarker,
+        }
+    }
+}
+
+/// Bullets are not rapier bodies, they move by their transform and
+/// sweep their path for hits in `bullets_update`.
+#[derive(Component)]
+pub struct Bullet {
+    velocity: Vec2,
+    lifespan: Timer,
+    damage: i32,
+    crit: bool,
+    pierce: u32,
+    bounce: u32,
+    /// Enemies already hit, each enemy is hit at most once.
+    hit: HashSet<Entity>,
+    source: DamageSource,
+}
+
+#[derive(Component)]
+pub struct BulletMarker;
+
+#[derive(Bundle)]
+pub struct BulletBundle {
+    bullet: Bullet,
+    marker: BulletMarker,
+}
+
+impl BulletBundle {
+    fn new(direction: Vec2, shot: &ShootEvent, weapon: &Weapon) -> Self {
+        let (velocity, lifespan) = match weapon.kind {
+            WeaponKind::Boomerang => (BOOMERANG_VELOCITY, weapon.duration),
+            _ => (BULLET_VELOCITY, BULLET_LIFETIME),
+        };
+        Self {
+            bullet: Bullet {
+                velocity: direction * velocity * weapon.speed,
+                lifespan: Timer::from_seconds(lifespan, TimerMode::Once),
+                damage: shot.damage,
+                crit: shot.crit,
+                pierce: weapon.pierce,
+                bounce: weapon.bounce,
+                hit: HashSet::new(),
+                source: shot.source,
+            },
+            marker: BulletMarker,
+        }
+    }
+}
+
+/// Cone width of weapons firing with a `Spread`.
+fn base_spread(kind: WeaponKind) -> Option<f32> {
+    match kind {
+        WeaponKind::Shotgun => Some(SHOTGUN_SPREAD),
+        WeaponKind::Boomerang => Some(BOOMERANG_SPREAD),
+        _ => None,
+    }
+}
+
+/// Spawns a weapon of the given kind with its kind specific components.
+pub fn spawn_weapon(builder: &mut ChildBuilder, kind: WeaponKind) {
+    let mut weapon = builder.spawn(WeaponBundle::new(kind));
+    if let Some(angle) = base_spread(kind) {
+        weapon.insert(Spread { angle });
+    }
+    match kind {
+        WeaponKind::Spiral => {
+            weapon.insert(Emitter::new(SPIRAL_PATTERN, Team::Player));
+        }
+        WeaponKind::Nova => {
+            weapon.insert(Emitter::new(NOVA_PATTERN, Team::Player));
+        }
+        _ => {}
+    }
+}
+
+/// Recomputes weapon values from the weapon's own stats and the player's.
+fn weapon_stats_update(
+    weapon_levels: Res<WeaponLevels>,
+    player: Query<Ref<Stats>, With<Player>>,
+    mut weapons: Query<(Ref<Stats>, &mut Weapon, Option<&mut Spread>), Without<Player>>,
+) {
+    let player = player.single();
+    for (stats, mut weapon, spread) in weapons.iter_mut() {
+        if !player.is_changed() && !stats.is_changed() {
+            continue;
+        }
+
+        let min_cooldown = weapon_levels
+            .tables
+            .get(&weapon.kind)
+            .map_or(MIN_ATTACK_COOLDOWN, |table| table.min_cooldown);
+        // the player's cooldown multiplier may go below the level table minimum,
+        // but never to zero
+        let cooldown = (stats.get(Stat::Cooldown).max(min_cooldown) * player.get(Stat::Cooldown))
+            .max(MIN_ATTACK_COOLDOWN);
+        weapon
+            .attack
+            .set_duration(Duration::from_secs_f32(cooldown));
+        weapon.damage = (stats.get(Stat::Damage) * player.get(Stat::Might)).round() as i32;
+        weapon.crit_chance = stats.get(Stat::CritChance) * player.get(Stat::Luck);
+        weapon.amount = (stats.get(Stat::Amount) + player.get(Stat::Amount)).max(0.0) as u32;
+        weapon.pierce = stats.get(Stat::Pierce).max(0.0) as u32;
+        weapon.bounce = stats.get(Stat::Bounce).max(0.0) as u32;
+        weapon.area = stats.get(Stat::Area) * player.get(Stat::Area);
+        weapon.duration = stats.get(Stat::Duration) * player.get(Stat::Duration);
+        weapon.speed = stats.get(Stat::Speed) * player.get(Stat::Speed);
+        if let Some(mut spread) = spread {
+            spread.angle = stats.get(Stat::Spread);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ShootEvent {
+    position: Vec3,
+    damage: i32,
+    crit: bool,
+    source: DamageSource,
+}
+
+/// Sent every time a weapon fires.
+#[derive(Debug)]
+pub struct WeaponFireEvent {
+    pub source: DamageSource,
+    pub projectiles: u32,
+}
+
+fn player_shoot(
+    time: Res<Time>,
+    targets: Targets,
+    enemies: Query<&Transform, With<Enemy>>,
+    mut shoot_event: EventWriter<ShootEvent>,
+    mut weapons: Query<(
+        Entity,
+        &mut Weapon,
+        &Targeting,
+        &mut Volley,
+        Option<&Spread>,
+    )>,
+) {
+    for (entity, mut weapon, targeting, mut volley, spread) in weapons.iter_mut() {
+        if !weapon.kind.is_projectile() {
+            continue;
+        }
+        let source = DamageSource {
+            weapon: entity,
+            kind: weapon.kind,
+        };
+
+        // repeated shots of the last volley
+        if !volley.queue.is_empty() && volley.timer.tick(time.delta()).just_finished() {
+            if let Some(target) = volley.queue.pop_front() {
+                let position = target
+                    .entity
+                    .and_then(|e| enemies.get(e).ok())
+                    .map_or(target.position, |transform| transform.translation);
+                let (damage, crit) = weapon.roll_damage();
+                shoot_event.send(ShootEvent {
+                    position,
+                    damage,
+                    crit,
+                    source,
+                });
+            }
+        }
+
+        if !weapon.attack.tick(time.delta()).finished() {
+            continue;
+        }
+
+        // spread weapons fire all their projectiles in a single cone
+        let amount = if spread.is_some() {
+            1
+        } else {
+            weapon.amount as usize
+        };
+        let found = targets.find_many(targets.aimed(*targeting), weapon.range, amount);
+        if found.is_empty() {
+            continue;
+        }
+
+        for target in found.iter() {
+            let (damage, crit) = weapon.roll_damage();
+            shoot_event.send(ShootEvent {
+                position: target.position,
+                damage,
+                crit,
+                source,
+            });
+        }
+        // not enough targets, the rest of the volley repeats at the same ones
+        volley.queue = (found.len()..amount)
+            .map(|n| found[n % found.len()])
+            .collect();
+        volley.timer.reset();
+    }
+}
+
+fn bullets_spawn(
+    game_assets: Res<GameAssets>,
+    player: Query<&Transform, With<Player>>,
+    weapons: Query<(&Weapon, Option<&Spread>)>,
+    mut commands: Commands,
+    mut events: EventReader<ShootEvent>,
+    mut fire_event: EventWriter<WeaponFireEvent>,
+) {
+    if events.is_empty() {
+        return;
+    }
+
+    let player_transform = player.single();
+
+    for shot in events.iter() {
+        let Ok((weapon, spread)) = weapons.get(shot.source.weapon) else {
+            continue;
+        };
+        let Some(direction) = (shot.position - player_transform.translation)
+            .truncate()
+            .try_normalize()
+        else {
+            continue;
+        };
+        let (amount, angle) = match spread {
+            Some(spread) => (weapon.amount, spread.angle),
+            None => (1, 0.0),
+        };
+
+        for n in 0..amount {
+            let offset = if amount > 1 {
+                angle * (n as f32 / (amount - 1) as f32 - 0.5)
+            } else {
+                0.0
+            };
+            let direction = Vec2::from_angle(offset).rotate(direction);
+
+            let mut bullet_transform = *player_transform;
+            bullet_transform.translation += (direction * 25.0).extend(0.0);
+
+            let mut bullet = commands.spawn(SpriteBundle {
+                transform: bullet_transform,
+                texture: game_assets.bullet.clone(),
+                ..default()
+            });
+            bullet.insert(BulletBundle::new(direction, shot, weapon));
+            if weapon.kind == WeaponKind::Boomerang {
+                bullet.insert(Boomerang::new(weapon.range));
+            }
+        }
+
+        fire_event.send(WeaponFireEvent {
+            source: shot.source,
+            projectiles: amount,
+        });
+    }
+}
+
+fn bullets_update(
+    time: Res<Time>,
+    enemies: Query<&Transform, With<Enemy>>,
+    rapier_context: Res<RapierContext>,
+    mut commands: Commands,
+    mut bullets: Query<(Entity, &mut Transform, &mut Bullet, Option<&Boomerang>), Without<Enemy>>,
+    mut damage_event: EventWriter<EnemyDamageEvent>,
+) {
+    for (entity, mut transform, mut bullet, boomerang) in bullets.iter_mut() {
+        if bullet.lifespan.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        // sweep the path the bullet travels this frame, so fast bullets
+        // can not skip over enemies at low frame rates
+        let position = transform.translation.truncate();
+        let motion = bullet.velocity * time.delta_seconds();
+        transform.translation += motion.extend(0.0);
+        let shape = Collider::ball(BULLET_RADIUS);
+        let mut hits = Vec::new();
+        loop {
+            let not_hit = |e| !bullet.hit.contains(&e) && !hits.iter().any(|(h, _)| *h == e);
+            let Some((enemy, toi)) = rapier_context.cast_shape(
+                position,
+                0.0,
+                motion,
+                &shape,
+                1.0,
+                enemy_filter().predicate(&not_hit),
+            ) else {
+                break;
+            };
+            hits.push((enemy, position + motion * toi.toi));
+        }
+
+        for (enemy, hit_position) in hits {
+            bullet.hit.insert(enemy);
+            damage_event.send(EnemyDamageEvent {
+                target: enemy,
+                damage: bullet.damage,
+                crit: bullet.crit,
+                source: bullet.source,
+                from_effect: false,
+            });
+
+            // boomerangs go through everything
+            if boomerang.is_some() {
+                continue;
+            }
+            if bullet.pierce > 0 {
+                bullet.pierce -= 1;
+                continue;
+            }
+            if bullet.bounce > 0 {
+                if let Some((_, target)) = nearest_enemy(
+                    &rapier_context,
+                    &enemies,
+                    hit_position.extend(transform.translation.z),
+                    BULLET_BOUNCE_RANGE,
+                    |e| bullet.hit.contains(&e),
+                ) {
+                    let direction = (target.truncate() - hit_position).normalize();
+                    bullet.bounce -= 1;
+                    bullet.velocity = direction * bullet.velocity.length();
+                    transform.translation = hit_position.extend(transform.translation.z);
+                    // the rest of the sweep is no longer on the bullet's path
+                    break;
+                }
+            }
+            commands.entity(entity).despawn();
+            break;
+        }
+    }
+}
+
+/// Nearest enemy within `radius` of `position` not matching `exclude`.
+pub fn nearest_enemy(
+    rapier_context: &RapierContext,
+    enemies: &Query<&Transform, With<Enemy>>,
+    position: Vec3,
+    radius: f32,
+    exclude: impl Fn(Entity) -> bool,
+) -> Option<(Entity, Vec3)> {
+    let mut nearest = None;
+    let mut length = f32::MAX;
+    rapier_context.intersections_with_shape(
+        position.truncate(),
+        0.0,
+        &Collider::ball(radius),
+        enemy_filter(),
+        |e| {
+            if !exclude(e) {
+                if let Ok(transform) = enemies.get(e) {
+                    let len = (transform.translation - position).length_squared();
+                    if len < length {
+                        length = len;
+                        nearest = Some((e, transform.translation));
+                    }
+                }
+            }
+            true
+        },
+    );
+    nearest
+}
@@ -0,0 +1,6 @@
+pub const NOVA_DAMAGE: i32 = 10;
+pub const NOVA_CRIT_CHANCE: f32 = 0.1;
+pub const NOVA_ATTACK_SPEED: f32 = 2.5;
+pub const NOVA_LIFETIME: f32 = 1.0;
+/// Name of the nova's pattern in `Patterns`. Firing is done by its `Emitter`.
+pub const NOVA_PATTERN: &str = "ring";
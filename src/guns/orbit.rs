@@ -0,0 +1,170 @@
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    collision::enemy_filter,
+    damage::{DamageSource, EnemyDamageEvent},
+    enemy::Enemy,
+    player::Player,
+    utils::remove_all_with,
+    GameAssets, GameState,
+};
+
+use super::{Weapon, WeaponFireEvent, WeaponKind};
+
+pub const ORBIT_DAMAGE: i32 = 8;
+pub const ORBIT_CRIT_CHANCE: f32 = 0.05;
+pub const ORBIT_ATTACK_SPEED: f32 = 5.0;
+pub const ORBIT_PROJECTILES: u32 = 2;
+pub const ORBIT_RADIUS: f32 = 120.0;
+pub const ORBIT_DURATION: f32 = 3.0;
+
+/// Radians per second.
+pub const ORBIT_ANGULAR_SPEED: f32 = 3.0;
+pub const ORBIT_PROJECTILE_RADIUS: f32 = 16.0;
+/// Minimum time between two hits of the same projectile on the same enemy.
+pub const ORBIT_HIT_COOLDOWN: f32 = 0.5;
+
+pub struct OrbitPlugin;
+
+impl Plugin for OrbitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems((orbit_spawn, orbit_update).in_set(OnUpdate(GameState::InGame)))
+            .add_system(remove_all_with::<OrbitMarker>.in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(remove_all_with::<OrbitMarker>.in_schedule(OnExit(GameState::GameOver)));
+    }
+}
+
+#[derive(Component)]
+pub struct OrbitProjectile {
+    angle: f32,
+    radius: f32,
+    lifespan: Timer,
+    /// Time of the last hit on each enemy.
+    last_hit: HashMap<Entity, f32>,
+    source: DamageSource,
+}
+
+#[derive(Component)]
+pub struct OrbitMarker;
+
+fn orbit_position(center: Vec3, angle: f32, radius: f32) -> Vec3 {
+    center + (Vec2::from_angle(angle) * radius).extend(0.0)
+}
+
+fn orbit_spawn(
+    time: Res<Time>,
+    game_assets: Res<GameAssets>,
+    player: Query<&Transform, With<Player>>,
+    mut commands: Commands,
+    mut weapons: Query<(Entity, &mut Weapon)>,
+    mut fire_event: EventWriter<WeaponFireEvent>,
+) {
+    let player_transform = player.single();
+
+    for (entity, mut weapon) in weapons.iter_mut() {
+        if weapon.kind != WeaponKind::Orbit || !weapon.attack.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let source = DamageSource {
+            weapon: entity,
+            kind: weapon.kind,
+        };
+        for n in 0..weapon.amount {
+            let angle = std::f32::consts::TAU * n as f32 / weapon.amount as f32;
+            commands
+                .spawn(SpriteBundle {
+                    transform: Transform::from_translation(orbit_position(
+                        player_transform.translation,
+                        angle,
+                        weapon.area,
+                    )),
+                    texture: game_assets.bullet.clone(),
+                    ..default()
+                })
+                .insert(OrbitProjectile {
+                    angle,
+                    radius: weapon.area,
+                    lifespan: Timer::from_seconds(weapon.duration, TimerMode::Once),
+                    last_hit: HashMap::new(),
+                    source,
+                })
+                .insert(OrbitMarker);
+        }
+
+        fire_event.send(WeaponFireEvent {
+            source,
+            projectiles: weapon.amount,
+        });
+    }
+}
+
+/// Entities orbit projectiles move around or hit.
+#[derive(SystemParam)]
+struct OrbitQueries<'w, 's> {
+    player: Query<'w, 's, &'static Transform, With<Player>>,
+    enemies: Query<'w, 's, (), With<Enemy>>,
+    weapons: Query<'w, 's, &'static Weapon>,
+}
+
+fn orbit_update(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    queries: OrbitQueries,
+    mut commands: Commands,
+    mut projectiles: Query<(Entity, &mut Transform, &mut OrbitProjectile), Without<Player>>,
+    mut damage_event: EventWriter<EnemyDamageEvent>,
+) {
+    let OrbitQueries {
+        player,
+        enemies,
+        weapons,
+    } = queries;
+    let player_transform = player.single();
+    let now = time.elapsed_seconds();
+
+    for (entity, mut transform, mut projectile) in projectiles.iter_mut() {
+        let Ok(weapon) = weapons.get(projectile.source.weapon) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        if projectile.lifespan.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        projectile.angle += ORBIT_ANGULAR_SPEED * weapon.speed * time.delta_seconds();
+        transform.translation = orbit_position(
+            player_transform.translation,
+            projectile.angle,
+            projectile.radius,
+        );
+
+        let source = projectile.source;
+        rapier_context.intersections_with_shape(
+            transform.translation.truncate(),
+            0.0,
+            &Collider::ball(ORBIT_PROJECTILE_RADIUS),
+            enemy_filter(),
+            |e| {
+                let ready = projectile
+                    .last_hit
+                    .get(&e)
+                    .is_none_or(|t| now - t >= ORBIT_HIT_COOLDOWN);
+                if ready && enemies.contains(e) {
+                    projectile.last_hit.insert(e, now);
+                    let (damage, crit) = weapon.roll_damage();
+                    damage_event.send(EnemyDamageEvent {
+                        target: e,
+                        damage,
+                        crit,
+                        source,
+                        from_effect: false,
+                    });
+                }
+                true
+            },
+        );
+    }
+}
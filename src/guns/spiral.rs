@@ -0,0 +1,7 @@
+pub const SPIRAL_DAMAGE: i32 = 6;
+pub const SPIRAL_CRIT_CHANCE: f32 = 0.05;
+pub const SPIRAL_RANGE: f32 = 600.0;
+pub const SPIRAL_ATTACK_SPEED: f32 = 4.0;
+pub const SPIRAL_LIFETIME: f32 = 1.5;
+/// Name of the spiral's pattern in `Patterns`. Firing is done by its `Emitter`.
+pub const SPIRAL_PATTERN: &str = "spiral";
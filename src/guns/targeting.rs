@@ -0,0 +1,213 @@
+use bevy::{ecs::system::SystemParam, prelude::*, window::PrimaryWindow};
+use bevy_rapier2d::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::{collision::enemy_filter, enemy::Enemy, player::Player, settings::Settings};
+
+use super::WeaponKind;
+
+/// Radius in which enemies count towards a cluster.
+pub const CLUSTER_RADIUS: f32 = 150.0;
+/// Right stick deflection below which the mouse is used for aiming.
+pub const AIM_STICK_DEADZONE: f32 = 0.3;
+
+pub struct TargetingPlugin;
+
+impl Plugin for TargetingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CursorPosition>()
+            .init_resource::<Aim>()
+            .add_systems((cursor_position_update, aim_update).chain());
+    }
+}
+
+/// How a weapon picks what to shoot at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum Targeting {
+    Nearest,
+    HighestHealth,
+    /// Random enemy in range.
+    Random,
+    /// Enemy with the most other enemies around it.
+    DensestCluster,
+    /// Player movement direction. Fires even without enemies around.
+    MovementDirection,
+    /// Manual aim. Fires even without enemies around.
+    Aim,
+}
+
+impl Targeting {
+    pub fn default_for(kind: WeaponKind) -> Self {
+        match kind {
+            WeaponKind::Shotgun => Targeting::DensestCluster,
+            WeaponKind::Lightning => Targeting::Random,
+            WeaponKind::Missile => Targeting::HighestHealth,
+            WeaponKind::Boomerang => Targeting::MovementDirection,
+            _ => Targeting::Nearest,
+        }
+    }
+}
+
+/// Cursor position in world coordinates.
+#[derive(Debug, Default, Resource)]
+pub struct CursorPosition {
+    pub world: Option<Vec2>,
+}
+
+fn cursor_position_update(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    mut cursor: ResMut<CursorPosition>,
+) {
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.get_single(), camera.get_single())
+    else {
+        return;
+    };
+    cursor.world = window
+        .cursor_position()
+        .and_then(|position| camera.viewport_to_world_2d(camera_transform, position));
+}
+
+/// Manual aim direction from the gamepad right stick or the mouse cursor.
+#[derive(Debug, Default, Resource)]
+pub struct Aim {
+    pub direction: Option<Vec2>,
+}
+
+fn aim_update(
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    cursor: Res<CursorPosition>,
+    player: Query<&Transform, With<Player>>,
+    mut aim: ResMut<Aim>,
+) {
+    let stick = gamepads.iter().find_map(|gamepad| {
+        let x = axes.get(GamepadAxis::new(gamepad, GamepadAxisType::RightStickX))?;
+        let y = axes.get(GamepadAxis::new(gamepad, GamepadAxisType::RightStickY))?;
+        let stick = Vec2::new(x, y);
+        (stick.length() > AIM_STICK_DEADZONE).then_some(stick)
+    });
+    let mouse = player.get_single().ok().and_then(|transform| {
+        cursor
+            .world
+            .map(|position| position - transform.translation.truncate())
+    });
+    aim.direction = stick
+        .or(mouse)
+        .and_then(|direction| direction.try_normalize());
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Target {
+    /// Targeted enemy, if the strategy picked one.
+    pub entity: Option<Entity>,
+    pub position: Vec3,
+}
+
+/// Everything needed to resolve a `Targeting` around the player.
+#[derive(SystemParam)]
+pub struct Targets<'w, 's> {
+    rapier_context: Res<'w, RapierContext>,
+    settings: Res<'w, Settings>,
+    aim: Res<'w, Aim>,
+    player: Query<'w, 's, (&'static Transform, &'static Player)>,
+    enemies: Query<'w, 's, (&'static Transform, &'static Enemy)>,
+}
+
+impl<'w, 's> Targets<'w, 's> {
+    /// The manual aim when it is enabled, `targeting` otherwise.
+    pub fn aimed(&self, targeting: Targeting) -> Targeting {
+        if self.settings.manual_aim {
+            Targeting::Aim
+        } else {
+            targeting
+        }
+    }
+
+    /// Like `find`, but follows the manual aim when it is enabled.
+    pub fn find_aimed(&self, targeting: Targeting, range: f32) -> Option<Target> {
+        self.find(self.aimed(targeting), range)
+    }
+
+    /// Finds a target within `range` of the player.
+    pub fn find(&self, targeting: Targeting, range: f32) -> Option<Target> {
+        self.find_many(targeting, range, 1).pop()
+    }
+
+    /// Finds up to `amount` distinct targets within `range` of the player, best first.
+    /// Direction based strategies always give a single target.
+    pub fn find_many(&self, targeting: Targeting, range: f32, amount: usize) -> Vec<Target> {
+        let (player_transform, player) = self.player.single();
+        let origin = player_transform.translation;
+
+        match targeting {
+            Targeting::MovementDirection => {
+                return vec![Target {
+                    entity: None,
+                    position: origin + (player.facing * range).extend(0.0),
+                }]
+            }
+            Targeting::Aim => {
+                return self
+                    .aim
+                    .direction
+                    .map(|direction| Target {
+                        entity: None,
+                        position: origin + (direction * range).extend(0.0),
+                    })
+                    .into_iter()
+                    .collect()
+            }
+            _ => {}
+        }
+
+        let mut in_range = Vec::new();
+        self.rapier_context.intersections_with_shape(
+            origin.truncate(),
+            0.0,
+            &Collider::ball(range),
+            enemy_filter(),
+            |e| {
+                if let Ok((transform, enemy)) = self.enemies.get(e) {
+                    in_range.push((e, transform.translation, enemy.health));
+                }
+                true
+            },
+        );
+
+        match targeting {
+            Targeting::Nearest => in_range.sort_by(|a, b| {
+                a.1.distance_squared(origin)
+                    .total_cmp(&b.1.distance_squared(origin))
+            }),
+            Targeting::HighestHealth => in_range.sort_by_key(|(_, _, health)| -health),
+            Targeting::Random => in_range.shuffle(&mut rand::thread_rng()),
+            Targeting::DensestCluster => {
+                let neighbours = |a: Vec3| {
+                    in_range
+                        .iter()
+                        .filter(|(_, b, _)| {
+                            a.distance_squared(*b) < CLUSTER_RADIUS * CLUSTER_RADIUS
+                        })
+                        .count()
+                };
+                let mut clusters = in_range
+                    .iter()
+                    .map(|target| (neighbours(target.1), *target))
+                    .collect::<Vec<_>>();
+                clusters.sort_by_key(|(count, _)| std::cmp::Reverse(*count));
+                in_range = clusters.into_iter().map(|(_, target)| target).collect();
+            }
+            Targeting::MovementDirection | Targeting::Aim => {}
+        }
+
+        in_range
+            .into_iter()
+            .take(amount)
+            .map(|(entity, position, _)| Target {
+                entity: Some(entity),
+                position,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,173 @@
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashSet};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    collision::enemy_filter,
+    damage::{DamageSource, EnemyDamageEvent},
+    enemy::Enemy,
+    player::Player,
+    utils::remove_all_with,
+    GameState,
+};
+
+use super::{Weapon, WeaponFireEvent, WeaponKind};
+
+pub const WHIP_DAMAGE: i32 = 15;
+pub const WHIP_CRIT_CHANCE: f32 = 0.15;
+pub const WHIP_ATTACK_SPEED: f32 = 1.2;
+/// Number of swings, spaced evenly around the player starting at the facing direction.
+pub const WHIP_AMOUNT: u32 = 1;
+/// Reach of a swing.
+pub const WHIP_REACH: f32 = 120.0;
+/// How long the hitbox of a swing stays out.
+pub const WHIP_SWING_TIME: f32 = 0.15;
+
+/// Full angle of the swing arc in radians.
+pub const WHIP_ARC: f32 = 1.2;
+pub const WHIP_WIDTH: f32 = 20.0;
+pub const WHIP_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.6);
+
+pub struct WhipPlugin;
+
+impl Plugin for WhipPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems((whip_swing, whip_update).in_set(OnUpdate(GameState::InGame)))
+            .add_system(remove_all_with::<WhipSwing>.in_schedule(OnExit(GameState::InGame)));
+    }
+}
+
+#[derive(Component)]
+pub struct WhipSwing {
+    direction: Vec2,
+    reach: f32,
+    lifespan: Timer,
+    hit: HashSet<Entity>,
+    source: DamageSource,
+}
+
+fn whip_swing(
+    time: Res<Time>,
+    player: Query<(&Transform, &Player)>,
+    mut commands: Commands,
+    mut weapons: Query<(Entity, &mut Weapon)>,
+    mut fire_event: EventWriter<WeaponFireEvent>,
+) {
+    let (player_transform, player) = player.single();
+
+    for (entity, mut weapon) in weapons.iter_mut() {
+        if weapon.kind != WeaponKind::Whip || !weapon.attack.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let source = DamageSource {
+            weapon: entity,
+            kind: weapon.kind,
+        };
+        let amount = weapon.amount;
+        for n in 0..amount {
+            let direction = Vec2::from_angle(std::f32::consts::TAU * n as f32 / amount as f32)
+                .rotate(player.facing);
+            commands
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: WHIP_COLOR,
+                        custom_size: Some(Vec2::new(weapon.area, WHIP_WIDTH)),
+                        ..default()
+                    },
+                    transform: swing_transform(
+                        player_transform.translation,
+                        direction,
+                        weapon.area,
+                    ),
+                    ..default()
+                })
+                .insert(WhipSwing {
+                    direction,
+                    reach: weapon.area,
+                    lifespan: Timer::from_seconds(WHIP_SWING_TIME, TimerMode::Once),
+                    hit: HashSet::new(),
+                    source,
+                });
+        }
+
+        fire_event.send(WeaponFireEvent {
+            source,
+            projectiles: amount,
+        });
+    }
+}
+
+/// Transform of a swing sprite reaching out of `origin`.
+fn swing_transform(origin: Vec3, direction: Vec2, reach: f32) -> Transform {
+    let mut transform = Transform::from_translation(origin + (direction * reach / 2.0).extend(0.0))
+        .with_rotation(Quat::from_rotation_z(direction.y.atan2(direction.x)));
+    // above characters
+    transform.translation.z = 2.0;
+    transform
+}
+
+/// Entities whip swings follow or hit.
+#[derive(SystemParam)]
+struct WhipQueries<'w, 's> {
+    player: Query<'w, 's, &'static Transform, With<Player>>,
+    enemies: Query<'w, 's, &'static Transform, (With<Enemy>, Without<WhipSwing>)>,
+    weapons: Query<'w, 's, &'static Weapon>,
+}
+
+fn whip_update(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    queries: WhipQueries,
+    mut commands: Commands,
+    mut swings: Query<(Entity, &mut Transform, &mut WhipSwing), Without<Player>>,
+    mut damage_event: EventWriter<EnemyDamageEvent>,
+) {
+    let WhipQueries {
+        player,
+        enemies,
+        weapons,
+    } = queries;
+    let player_transform = player.single();
+
+    for (entity, mut transform, mut swing) in swings.iter_mut() {
+        let Ok(weapon) = weapons.get(swing.source.weapon) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        if swing.lifespan.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        // the swing follows the player
+        *transform = swing_transform(player_transform.translation, swing.direction, swing.reach);
+
+        let swing = swing.as_mut();
+        rapier_context.intersections_with_shape(
+            player_transform.translation.truncate(),
+            0.0,
+            &Collider::ball(swing.reach),
+            enemy_filter(),
+            |e| {
+                let Ok(enemy_transform) = enemies.get(e) else {
+                    return true;
+                };
+                let to_enemy =
+                    (enemy_transform.translation - player_transform.translation).truncate();
+                let in_arc = to_enemy == Vec2::ZERO
+                    || swing.direction.angle_between(to_enemy).abs() <= WHIP_ARC / 2.0;
+                if in_arc && swing.hit.insert(e) {
+                    let (damage, crit) = weapon.roll_damage();
+                    damage_event.send(EnemyDamageEvent {
+                        target: e,
+                        damage,
+                        crit,
+                        source: swing.source,
+                        from_effect: false,
+                    });
+                }
+                true
+            },
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use bevy::{ecs::system::SystemParam, prelude::*, sprite::MaterialMesh2dBundle};
+use bevy_rapier2d::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::{
+    collision::enemy_filter,
+    damage::{DamageSource, EnemyDamageEvent},
+    enemy::Enemy,
+    player::Player,
+    utils::remove_all_with,
+    GameState,
+};
+
+use super::{Weapon, WeaponFireEvent, WeaponKind};
+
+/// Damage per tick.
+pub const ZONE_DAMAGE: i32 = 4;
+pub const ZONE_CRIT_CHANCE: f32 = 0.0;
+/// Range in which a target enemy is picked.
+pub const ZONE_RANGE: f32 = 500.0;
+pub const ZONE_ATTACK_SPEED: f32 = 3.0;
+pub const ZONE_AMOUNT: u32 = 1;
+pub const ZONE_RADIUS: f32 = 60.0;
+pub const ZONE_DURATION: f32 = 3.0;
+
+/// Time between damage ticks.
+pub const ZONE_TICK: f32 = 0.4;
+pub const ZONE_COLOR: Color = Color::rgba(0.2, 0.4, 1.0, 0.3);
+
+pub struct ZonePlugin;
+
+impl Plugin for ZonePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ZoneAssets>()
+            .add_systems((zone_spawn, zone_update).in_set(OnUpdate(GameState::InGame)))
+            .add_system(remove_all_with::<Zone>.in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(remove_all_with::<Zone>.in_schedule(OnExit(GameState::GameOver)));
+    }
+}
+
+/// Mesh and material shared by all zones.
+#[derive(Resource)]
+pub struct ZoneAssets {
+    /// Unit circle, scaled to the zone's radius.
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+impl FromWorld for ZoneAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(shape::Circle::new(1.0).into());
+        let material = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(ColorMaterial::from(ZONE_COLOR));
+        Self { mesh, material }
+    }
+}
+
+#[derive(Component)]
+pub struct Zone {
+    radius: f32,
+    lifespan: Timer,
+    tick: Timer,
+    source: DamageSource,
+}
+
+/// Entities zones are placed around.
+#[derive(SystemParam)]
+struct ZoneQueries<'w, 's> {
+    player: Query<'w, 's, &'static Transform, With<Player>>,
+    enemies: Query<'w, 's, &'static Transform, With<Enemy>>,
+    weapons: Query<'w, 's, (Entity, &'static mut Weapon)>,
+}
+
+fn zone_spawn(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    queries: ZoneQueries,
+    zone_assets: Res<ZoneAssets>,
+    mut commands: Commands,
+    mut fire_event: EventWriter<WeaponFireEvent>,
+) {
+    let ZoneQueries {
+        player,
+        enemies,
+        mut weapons,
+    } = queries;
+    let player_transform = player.single();
+
+    for (entity, mut weapon) in weapons.iter_mut() {
+        if weapon.kind != WeaponKind::Zone || !weapon.attack.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let mut in_range = Vec::new();
+        rapier_context.intersections_with_shape(
+            player_transform.translation.truncate(),
+            0.0,
+            &Collider::ball(weapon.range),
+            enemy_filter(),
+            |e| {
+                if let Ok(transform) = enemies.get(e) {
+                    in_range.push(transform.translation);
+                }
+                true
+            },
+        );
+        // drop under the player when nobody is around
+        let positions = if in_range.is_empty() {
+            vec![player_transform.translation]
+        } else {
+            in_range
+                .choose_multiple(&mut rand::thread_rng(), weapon.amount as usize)
+                .copied()
+                .collect()
+        };
+
+        let source = DamageSource {
+            weapon: entity,
+            kind: weapon.kind,
+        };
+        for position in positions.iter() {
+            commands
+                .spawn(MaterialMesh2dBundle {
+                    mesh: zone_assets.mesh.clone().into(),
+                    material: zone_assets.material.clone(),
+                    // under characters
+                    transform: Transform::from_xyz(position.x, position.y, -0.5)
+                        .with_scale(Vec3::splat(weapon.area)),
+                    ..default()
+                })
+                .insert(Zone {
+                    radius: weapon.area,
+                    lifespan: Timer::from_seconds(weapon.duration, TimerMode::Once),
+                    tick: Timer::from_seconds(ZONE_TICK, TimerMode::Repeating),
+                    source,
+                });
+        }
+
+        fire_event.send(WeaponFireEvent {
+            source,
+            projectiles: positions.len() as u32,
+        });
+    }
+}
+
+fn zone_update(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    enemies: Query<(), With<Enemy>>,
+    weapons: Query<&Weapon>,
+    mut commands: Commands,
+    mut zones: Query<(Entity, &Transform, &mut Zone)>,
+    mut damage_event: EventWriter<EnemyDamageEvent>,
+) {
+    for (entity, transform, mut zone) in zones.iter_mut() {
+        let Ok(weapon) = weapons.get(zone.source.weapon) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        if zone.lifespan.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if !zone.tick.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        rapier_context.intersections_with_shape(
+            transform.translation.truncate(),
+            0.0,
+            &Collider::ball(zone.radius),
+            enemy_filter(),
+            |e| {
+                if enemies.contains(e) {
+                    let (damage, crit) = weapon.roll_damage();
+                    damage_event.send(EnemyDamageEvent {
+                        target: e,
+                        damage,
+                        crit,
+                        source: zone.source,
+                        from_effect: false,
+                    });
+                }
+                true
+            },
+        );
+    }
+}
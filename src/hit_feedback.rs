@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+
+use crate::{
+    damage::{EnemyDamageEvent, EnemyDeathEvent},
+    enemy::{Enemy, EnemyQuality},
+    player::player_death,
+    settings::Settings,
+    stats::{Stat, Stats},
+    GameState,
+};
+
+pub const HIT_FLASH_TIME: f32 = 0.08;
+/// Values above 1.0 saturate the sprite texture to white.
+pub const HIT_FLASH_COLOR: Color = Color::rgb(10.0, 10.0, 10.0);
+
+/// A single hit triggers hit-stop when it deals this many times the target's max health.
+/// Killing a legendary enemy always triggers it.
+pub const HIT_STOP_OVERKILL: f32 = 2.0;
+/// Hit-stop duration in real (unscaled) seconds.
+pub const HIT_STOP_TIME: f32 = 0.05;
+pub const HIT_STOP_SPEED: f32 = 0.05;
+/// Real seconds from the start of a hit-stop until the next one can start.
+pub const HIT_STOP_COOLDOWN: f32 = 1.0;
+
+pub struct HitFeedbackPlugin;
+
+impl Plugin for HitFeedbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HitStop>()
+            .add_systems((hit_flash_start, hit_flash_update).in_set(OnUpdate(GameState::InGame)))
+            // the death slow-mo wins over hit-stop
+            .add_systems(
+                (hit_stop_start, hit_stop_update)
+                    .chain()
+                    .before(player_death)
+                    .in_set(OnUpdate(GameState::InGame)),
+            )
+            .add_system(hit_stop_reset.in_schedule(OnExit(GameState::InGame)));
+    }
+}
+
+/// Part of every enemy, restarted on each hit.
+#[derive(Component)]
+pub struct HitFlash {
+    timer: Timer,
+}
+
+impl Default for HitFlash {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(HIT_FLASH_TIME, TimerMode::Once);
+        timer.tick(timer.duration());
+        Self { timer }
+    }
+}
+
+#[derive(Resource)]
+pub struct HitStop {
+    timer: Timer,
+    cooldown: Timer,
+}
+
+impl Default for HitStop {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(HIT_STOP_TIME, TimerMode::Once);
+        timer.tick(timer.duration());
+        let mut cooldown = Timer::from_seconds(HIT_STOP_COOLDOWN, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Self { timer, cooldown }
+    }
+}
+
+fn hit_flash_start(
+    settings: Res<Settings>,
+    mut events: EventReader<EnemyDamageEvent>,
+    mut flashes: Query<(&mut HitFlash, &mut Sprite)>,
+) {
+    if !settings.hit_flash {
+        events.clear();
+        return;
+    }
+
+    for event in events.iter() {
+        if let Ok((mut flash, mut sprite)) = flashes.get_mut(event.target) {
+            flash.timer.reset();
+            sprite.color = HIT_FLASH_COLOR;
+        }
+    }
+}
+
+fn hit_flash_update(time: Res<Time>, mut flashes: Query<(&mut HitFlash, &mut Sprite)>) {
+    for (mut flash, mut sprite) in flashes.iter_mut() {
+        if flash.timer.tick(time.delta()).just_finished() {
+            sprite.color = Color::WHITE;
+        }
+    }
+}
+
+fn hit_stop_start(
+    settings: Res<Settings>,
+    enemies: Query<&Stats, With<Enemy>>,
+    mut time: ResMut<Time>,
+    mut hit_stop: ResMut<HitStop>,
+    mut damage_events: EventReader<EnemyDamageEvent>,
+    mut death_events: EventReader<EnemyDeathEvent>,
+) {
+    // an active stop is never restarted
+    if !settings.hit_stop || !hit_stop.cooldown.finished() {
+        damage_events.clear();
+        death_events.clear();
+        return;
+    }
+
+    let heavy_hit = damage_events.iter().any(|event| {
+        !event.from_effect
+            && enemies.get(event.target).is_ok_and(|stats| {
+                event.damage as f32 >= stats.get(Stat::MaxHealth) * HIT_STOP_OVERKILL
+            })
+    });
+    let legendary_kill = death_events
+        .iter()
+        .any(|event| event.quality == EnemyQuality::Legendary);
+    if heavy_hit || legendary_kill {
+        time.set_relative_speed(HIT_STOP_SPEED);
+        hit_stop.timer.reset();
+        hit_stop.cooldown.reset();
+    }
+}
+
+fn hit_stop_update(mut time: ResMut<Time>, mut hit_stop: ResMut<HitStop>) {
+    // ticked in real time, game time is slowed down
+    let delta = time.raw_delta();
+    hit_stop.cooldown.tick(delta);
+    if hit_stop.timer.tick(delta).just_finished() {
+        time.set_relative_speed(1.0);
+    }
+}
+
+fn hit_stop_reset(mut time: ResMut<Time>, mut commands: Commands) {
+    time.set_relative_speed(1.0);
+    commands.insert_resource(HitStop::default());
+}
@@ -1,10 +1,18 @@
 use bevy::{input::mouse::MouseWheel, prelude::*};
 use bevy_rapier2d::prelude::*;
 
+mod collision;
+mod combat_stats;
 mod damage;
+mod effects;
 mod enemy;
+mod evolution;
 mod guns;
+mod hit_feedback;
+mod patterns;
 mod player;
+mod settings;
+mod stats;
 mod ui;
 mod utils;
 
@@ -14,13 +22,21 @@ fn main() {
     App::new()
         .insert_resource(ClearColor(Color::BLACK))
         .add_state::<GameState>()
+        .init_resource::<settings::Settings>()
         .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
         .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(1000.0))
         .add_plugin(RapierDebugRenderPlugin::default())
+        .add_plugin(combat_stats::CombatStatsPlugin)
         .add_plugin(damage::DamagePlugin)
+        .add_plugin(effects::EffectsPlugin)
         .add_plugin(enemy::EnemyPlugin)
         .add_plugin(guns::GunsPlugin)
+        // checks recipes against the weapon level tables
+        .add_plugin(evolution::EvolutionPlugin)
+        .add_plugin(hit_feedback::HitFeedbackPlugin)
+        .add_plugin(patterns::PatternsPlugin)
         .add_plugin(player::PlayerPlugin)
+        .add_plugin(stats::StatsPlugin)
         .add_plugin(ui::UiPlugin)
         .add_startup_system(setup)
         .add_system(camera_zoom)
@@ -33,6 +49,7 @@ pub enum GameState {
     MainMenu,
     InGame,
     LevelUp,
+    GameOver,
 }
 impl_into_state!(GameState);
 
@@ -0,0 +1,496 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    collision::{enemy_filter, player_filter},
+    damage::{DamageSource, EnemyDamageEvent, PlayerDamageEvent},
+    enemy::EnemyQuality,
+    guns::{Targeting, Targets, Weapon, WeaponFireEvent, WEAPON_PATTERNS},
+    player::Player,
+    utils::remove_all_with,
+    GameAssets, GameState,
+};
+
+/// Named patterns, looked up by `Emitter::pattern`.
+const PATTERNS: &str = include_str!("../assets/patterns.ron");
+
+pub const PATTERN_BULLET_RADIUS: f32 = 6.0;
+pub const ENEMY_BULLET_COLOR: Color = Color::rgb(1.0, 0.3, 0.3);
+
+pub struct PatternsPlugin;
+
+impl Plugin for PatternsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Patterns::from_ron(PATTERNS).expect("invalid projectile patterns"))
+            .add_systems(
+                (emitter_update, pattern_bullets_update).in_set(OnUpdate(GameState::InGame)),
+            )
+            .add_system(remove_all_with::<PatternBullet>.in_schedule(OnExit(GameState::InGame)));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum Shape {
+    /// Bullets evenly around a circle.
+    Ring { bullets: u32 },
+    /// Bullets over a cone of `angle` radians toward the target.
+    Fan { bullets: u32, angle: f32 },
+    /// Evenly spaced arms, turning by `step` radians every shot.
+    Spiral { arms: u32, step: f32 },
+    /// A single bullet swinging around the target direction.
+    Wave { amplitude: f32, frequency: f32 },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Pattern {
+    pub shape: Shape,
+    /// Shots per burst.
+    pub shots: u32,
+    /// Seconds between shots of a burst.
+    pub interval: f32,
+    pub speed: f32,
+    /// Seconds from the end of a burst to the start of the next one.
+    pub cooldown: f32,
+    pub damage: i32,
+    pub lifetime: f32,
+    /// Enemies of these qualities spawn with an emitter firing this pattern.
+    #[serde(default)]
+    pub enemies: Vec<EnemyQuality>,
+}
+
+impl Pattern {
+    fn validate(&self) -> Result<(), String> {
+        if self.cooldown <= 0.0 {
+            return Err("cooldown must be positive".into());
+        }
+        if self.interval < 0.0 || self.lifetime < 0.0 {
+            return Err("interval and lifetime can not be negative".into());
+        }
+        if self.shots == 0 {
+            return Err("shots must be at least 1".into());
+        }
+        match self.shape {
+            Shape::Ring { bullets } | Shape::Fan { bullets, .. } if bullets == 0 => {
+                Err("bullets must be at least 1".into())
+            }
+            Shape::Spiral { arms: 0, .. } => Err("arms must be at least 1".into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Bullet directions of a shot fired `elapsed` seconds into the burst.
+    fn directions(&self, aim: Vec2, angle: f32, elapsed: f32) -> Vec<Vec2> {
+        let around = |n: u32, bullets: u32| {
+            Vec2::from_angle(angle + std::f32::consts::TAU * n as f32 / bullets as f32).rotate(aim)
+        };
+        match self.shape {
+            Shape::Ring { bullets } => (0..bullets).map(|n| around(n, bullets)).collect(),
+            Shape::Spiral { arms, .. } => (0..arms).map(|n| around(n, arms)).collect(),
+            Shape::Fan { bullets, angle } => (0..bullets)
+                .map(|n| {
+                    let offset = if bullets > 1 {
+                        angle * (n as f32 / (bullets - 1) as f32 - 0.5)
+                    } else {
+                        0.0
+                    };
+                    Vec2::from_angle(offset).rotate(aim)
+                })
+                .collect(),
+            Shape::Wave {
+                amplitude,
+                frequency,
+            } => {
+                let offset = amplitude * (std::f32::consts::TAU * frequency * elapsed).sin();
+                vec![Vec2::from_angle(offset).rotate(aim)]
+            }
+        }
+    }
+}
+
+#[derive(Debug, Resource)]
+pub struct Patterns {
+    pub patterns: HashMap<String, Pattern>,
+}
+
+impl Patterns {
+    /// Parses the patterns, rejecting values that would stall or panic an emitter,
+    /// missing weapon patterns and enemies firing more than one pattern.
+    pub fn from_ron(source: &str) -> Result<Self, String> {
+        let patterns: HashMap<String, Pattern> =
+            ron::from_str(source).map_err(|e| e.to_string())?;
+        for (name, pattern) in patterns.iter() {
+            pattern
+                .validate()
+                .map_err(|e| format!("pattern {name:?}: {e}"))?;
+        }
+        for name in WEAPON_PATTERNS {
+            if !patterns.contains_key(name) {
+                return Err(format!("missing weapon pattern {name:?}"));
+            }
+        }
+        let mut enemies = HashSet::new();
+        for quality in patterns.values().flat_map(|pattern| pattern.enemies.iter()) {
+            if !enemies.insert(quality) {
+                return Err(format!(
+                    "{quality:?} enemies are listed by more than one pattern"
+                ));
+            }
+        }
+        Ok(Self { patterns })
+    }
+
+    /// Name of the pattern fired by enemies of `quality`, if any.
+    pub fn for_enemy(&self, quality: EnemyQuality) -> Option<&str> {
+        self.patterns
+            .iter()
+            .find(|(_, pattern)| pattern.enemies.contains(&quality))
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Team {
+    Player,
+    Enemy,
+}
+
+/// Fires a pattern from `Patterns`. On a weapon the weapon's cooldown,
+/// damage, duration and speed are used instead of the pattern's,
+/// and each point of amount above one adds a shot to the burst.
+#[derive(Component)]
+pub struct Emitter {
+    pub pattern: String,
+    pub team: Team,
+    /// Created from the pattern on the first update, unused on weapons.
+    cooldown: Option<Timer>,
+    shots_left: u32,
+    shot: Timer,
+    /// Rotation carried between shots, used by spirals.
+    angle: f32,
+    /// Seconds since the burst started, used by waves.
+    elapsed: f32,
+}
+
+impl Emitter {
+    pub fn new(pattern: &str, team: Team) -> Self {
+        Self {
+            pattern: pattern.into(),
+            team,
+            cooldown: None,
+            shots_left: 0,
+            shot: Timer::default(),
+            angle: 0.0,
+            elapsed: 0.0,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct PatternBullet {
+    velocity: Vec2,
+    lifespan: Timer,
+    damage: i32,
+    crit: bool,
+    team: Team,
+    /// Set for bullets fired by a weapon.
+    source: Option<DamageSource>,
+}
+
+/// Emitters and the player they aim from.
+#[derive(SystemParam)]
+struct EmitterQueries<'w, 's> {
+    player: Query<'w, 's, &'static Transform, With<Player>>,
+    emitters: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static GlobalTransform,
+            &'static mut Emitter,
+            Option<&'static mut Weapon>,
+            Option<&'static Targeting>,
+        ),
+    >,
+}
+
+fn emitter_update(
+    time: Res<Time>,
+    game_assets: Res<GameAssets>,
+    patterns: Res<Patterns>,
+    targets: Targets,
+    queries: EmitterQueries,
+    mut commands: Commands,
+    mut fire_event: EventWriter<WeaponFireEvent>,
+) {
+    let EmitterQueries {
+        player,
+        mut emitters,
+    } = queries;
+    let player_transform = player.single();
+
+    for (entity, transform, mut emitter, mut weapon, targeting) in emitters.iter_mut() {
+        let Some(pattern) = patterns.patterns.get(&emitter.pattern) else {
+            continue;
+        };
+
+        // the cooldown only runs between bursts, a burst always finishes
+        let burst = emitter.shots_left == 0
+            && match weapon.as_mut() {
+                Some(weapon) => weapon.attack.tick(time.delta()).just_finished(),
+                None => emitter
+                    .cooldown
+                    .get_or_insert_with(|| {
+                        Timer::from_seconds(pattern.cooldown, TimerMode::Repeating)
+                    })
+                    .tick(time.delta())
+                    .just_finished(),
+            };
+        if burst {
+            // extra weapon amount adds shots to the burst
+            let extra = weapon
+                .as_ref()
+                .map_or(0, |weapon| weapon.amount.saturating_sub(1));
+            emitter.shots_left = pattern.shots + extra;
+            emitter.elapsed = 0.0;
+            let mut shot = Timer::from_seconds(pattern.interval, TimerMode::Once);
+            // first shot on this tick
+            shot.set_elapsed(shot.duration());
+            emitter.shot = shot;
+        } else {
+            emitter.elapsed += time.delta_seconds();
+        }
+        if emitter.shots_left == 0 || !emitter.shot.tick(time.delta()).finished() {
+            continue;
+        }
+        emitter.shot.reset();
+        emitter.shots_left -= 1;
+
+        let position = transform.translation();
+        let target = match (emitter.team, weapon.as_ref(), targeting) {
+            (Team::Player, Some(weapon), Some(targeting)) => targets
+                .find_aimed(*targeting, weapon.range)
+                .map(|target| target.position),
+            (Team::Player, _, _) => None,
+            (Team::Enemy, _, _) => Some(player_transform.translation),
+        };
+        let aim = target
+            .and_then(|target| (target - position).truncate().try_normalize())
+            .unwrap_or(Vec2::X);
+
+        let directions = pattern.directions(aim, emitter.angle, emitter.elapsed);
+        if let Shape::Spiral { step, .. } = pattern.shape {
+            emitter.angle += step;
+        }
+
+        let source = weapon.as_ref().map(|weapon| DamageSource {
+            weapon: entity,
+            kind: weapon.kind,
+        });
+        let (speed, lifetime) = match weapon.as_ref() {
+            Some(weapon) => (pattern.speed * weapon.speed, weapon.duration),
+            None => (pattern.speed, pattern.lifetime),
+        };
+        let color = match emitter.team {
+            Team::Player => Color::WHITE,
+            Team::Enemy => ENEMY_BULLET_COLOR,
+        };
+        for direction in directions.iter() {
+            let (damage, crit) = match weapon.as_ref() {
+                Some(weapon) => weapon.roll_damage(),
+                None => (pattern.damage, false),
+            };
+            commands
+                .spawn(SpriteBundle {
+                    sprite: Sprite { color, ..default() },
+                    transform: Transform::from_translation(position),
+                    texture: game_assets.bullet.clone(),
+                    ..default()
+                })
+                .insert(PatternBullet {
+                    velocity: *direction * speed,
+                    lifespan: Timer::from_seconds(lifetime, TimerMode::Once),
+                    damage,
+                    crit,
+                    team: emitter.team,
+                    source,
+                });
+        }
+
+        if let Some(source) = source {
+            fire_event.send(WeaponFireEvent {
+                source,
+                projectiles: directions.len() as u32,
+            });
+        }
+    }
+}
+
+fn pattern_bullets_update(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    mut commands: Commands,
+    mut bullets: Query<(Entity, &mut Transform, &mut PatternBullet)>,
+    mut enemy_damage_event: EventWriter<EnemyDamageEvent>,
+    mut player_damage_event: EventWriter<PlayerDamageEvent>,
+) {
+    for (entity, mut transform, mut bullet) in bullets.iter_mut() {
+        if bullet.lifespan.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // sweep the path travelled this frame, like player bullets,
+        // so fast patterns can not skip over their targets
+        let position = transform.translation.truncate();
+        let motion = bullet.velocity * time.delta_seconds();
+        transform.translation += motion.extend(0.0);
+
+        let filter = match bullet.team {
+            Team::Player => enemy_filter(),
+            Team::Enemy => player_filter(),
+        };
+        let Some((target, _)) = rapier_context.cast_shape(
+            position,
+            0.0,
+            motion,
+            &Collider::ball(PATTERN_BULLET_RADIUS),
+            1.0,
+            filter,
+        ) else {
+            continue;
+        };
+
+        match (bullet.team, bullet.source) {
+            (Team::Player, Some(source)) => enemy_damage_event.send(EnemyDamageEvent {
+                target,
+                damage: bullet.damage,
+                crit: bullet.crit,
+                source,
+                from_effect: false,
+            }),
+            (Team::Player, None) => {}
+            (Team::Enemy, _) => player_damage_event.send(PlayerDamageEvent {
+                damage: bullet.damage,
+            }),
+        }
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    use super::*;
+
+    fn pattern(shape: Shape) -> Pattern {
+        Pattern {
+            shape,
+            shots: 1,
+            interval: 0.0,
+            speed: 1.0,
+            cooldown: 1.0,
+            damage: 1,
+            lifetime: 1.0,
+            enemies: Vec::new(),
+        }
+    }
+
+    fn assert_directions(directions: Vec<Vec2>, expected: &[Vec2]) {
+        assert_eq!(directions.len(), expected.len());
+        for (direction, expected) in directions.iter().zip(expected) {
+            assert!(
+                direction.abs_diff_eq(*expected, 1e-5),
+                "{direction} != {expected}"
+            );
+        }
+    }
+
+    /// Source with the weapon patterns plus `extra` entries.
+    fn source(extra: &str) -> String {
+        let entry = "(shape: Ring(bullets: 1), shots: 1, interval: 0.0, speed: 1.0, \
+                     cooldown: 1.0, damage: 1, lifetime: 1.0";
+        let weapons = WEAPON_PATTERNS
+            .iter()
+            .map(|name| format!("{name:?}: {entry}),"))
+            .collect::<String>();
+        format!("{{{weapons}{}}}", extra.replace("ENTRY", entry))
+    }
+
+    #[test]
+    fn patterns_load() {
+        Patterns::from_ron(PATTERNS).unwrap();
+    }
+
+    #[test]
+    fn missing_weapon_pattern_is_rejected() {
+        let error = Patterns::from_ron("{}").unwrap_err();
+        assert!(error.contains("missing weapon pattern"), "{error}");
+        Patterns::from_ron(&source("")).unwrap();
+    }
+
+    #[test]
+    fn enemy_patterns_are_unique() {
+        let patterns = Patterns::from_ron(&source(r#""a": ENTRY, enemies: [Rare]),"#)).unwrap();
+        assert_eq!(patterns.for_enemy(EnemyQuality::Rare), Some("a"));
+        assert_eq!(patterns.for_enemy(EnemyQuality::Legendary), None);
+
+        let twice = source(r#""a": ENTRY, enemies: [Rare]), "b": ENTRY, enemies: [Rare]),"#);
+        assert!(Patterns::from_ron(&twice).is_err());
+    }
+
+    #[test]
+    fn ring_and_spiral_spread_evenly() {
+        let ring = pattern(Shape::Ring { bullets: 4 });
+        assert_directions(
+            ring.directions(Vec2::X, 0.0, 0.0),
+            &[Vec2::X, Vec2::Y, Vec2::NEG_X, Vec2::NEG_Y],
+        );
+        // the spiral's carried angle turns every arm
+        let spiral = pattern(Shape::Spiral { arms: 2, step: 0.1 });
+        assert_directions(
+            spiral.directions(Vec2::X, FRAC_PI_2, 0.0),
+            &[Vec2::Y, Vec2::NEG_Y],
+        );
+    }
+
+    #[test]
+    fn fan_covers_the_cone_toward_the_aim() {
+        let fan = pattern(Shape::Fan {
+            bullets: 3,
+            angle: FRAC_PI_2,
+        });
+        assert_directions(
+            fan.directions(Vec2::Y, 0.0, 0.0),
+            &[
+                Vec2::from_angle(FRAC_PI_4),
+                Vec2::Y,
+                Vec2::from_angle(3.0 * FRAC_PI_4),
+            ],
+        );
+        let single = pattern(Shape::Fan {
+            bullets: 1,
+            angle: FRAC_PI_2,
+        });
+        assert_directions(single.directions(Vec2::Y, 0.0, 0.0), &[Vec2::Y]);
+    }
+
+    #[test]
+    fn wave_swings_around_the_aim() {
+        let wave = pattern(Shape::Wave {
+            amplitude: 0.5,
+            frequency: 1.0,
+        });
+        assert_directions(wave.directions(Vec2::X, 0.0, 0.0), &[Vec2::X]);
+        assert_directions(
+            wave.directions(Vec2::X, 0.0, 0.25),
+            &[Vec2::from_angle(0.5)],
+        );
+        assert_directions(
+            wave.directions(Vec2::X, 0.0, 0.75),
+            &[Vec2::from_angle(-0.5)],
+        );
+    }
+}
@@ -2,25 +2,35 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::{
+    collision::PLAYER_COLLISION_GROUPS,
+    effects::{Effect, EffectTrigger, Effects},
     enemy::{EnemyWave, Experience},
-    guns::Gun,
+    evolution::{Evolutions, Evolved},
+    guns::{spawn_weapon, Weapon, WeaponKind, WeaponLevels, MAX_WEAPONS},
+    stats::{Modifier, ModifierKind, ModifierSource, Stat, StatTables, Stats},
     utils::remove_all_with,
     GameAssets, GameState,
 };
 
 pub const CHARACTER_RADIUS: f32 = 20.0;
 
-pub const PLAYER_SPEED: f32 = 120.0;
-pub const PLAYER_HEALTH: i32 = 100;
 pub const PLAYER_MOVEMENT_FORCE: f32 = 1000.0;
 
-pub const PLAYER_GUN_DAMAGE: i32 = 10;
-pub const PLAYER_GUN_RANGE: f32 = 900.0;
-pub const PLAYER_ATTACKSPEED: f32 = 0.5;
+/// Length of the death animation in real (unscaled) seconds.
+pub const PLAYER_DEATH_TIME: f32 = 1.5;
+pub const PLAYER_DEATH_SLOW_MO: f32 = 0.2;
 
 pub const PLAYER_PULL_EXP_RANGE: f32 = 600.0;
 pub const PLAYER_COLLECT_EXP_RANGE: f32 = 10.0;
 
+pub const UPGRADE_LIFESTEAL_FRACTION: f32 = 0.1;
+pub const UPGRADE_CHAIN_DAMAGE: i32 = 10;
+pub const UPGRADE_CHAIN_RANGE: f32 = 300.0;
+pub const UPGRADE_EXPLOSION_DAMAGE: i32 = 10;
+pub const UPGRADE_EXPLOSION_RADIUS: f32 = 100.0;
+pub const UPGRADE_SPEED_BOOST: f32 = 60.0;
+pub const UPGRADE_SPEED_BOOST_DURATION: f32 = 2.0;
+
 pub const EXP_SPEED: f32 = 400.0;
 pub const LEVEL_UP_EXP: u32 = 50;
 
@@ -33,25 +43,38 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<PlayerUpgradeEvent>()
-            .add_system(setup.in_schedule(OnExit(GameState::MainMenu)))
+            // a new run starts when entering the game without a player
+            .add_system(
+                setup
+                    .run_if(not(any_with_component::<Player>()))
+                    .in_schedule(OnEnter(GameState::InGame)),
+            )
             .add_systems(
                 (player_movement, player_exp, player_upgrade, player_death)
                     .in_set(OnUpdate(GameState::InGame)),
             )
-            .add_system(remove_all_with::<PlayerMarker>.in_schedule(OnEnter(GameState::MainMenu)));
+            .add_system(remove_all_with::<PlayerMarker>.in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(remove_all_with::<PlayerMarker>.in_schedule(OnExit(GameState::GameOver)));
     }
 }
 
 #[derive(Component)]
 pub struct Player {
     pub health: i32,
-    pub speed: f32,
     pub exp: u32,
+    pub level: u32,
+    /// Last movement direction, normalized.
+    pub facing: Vec2,
 }
 
 #[derive(Component)]
 pub struct PlayerMarker;
 
+#[derive(Component)]
+pub struct PlayerDying {
+    timer: Timer,
+}
+
 #[derive(Bundle)]
 pub struct CharacterBundle {
     rigid_body: RigidBody,
@@ -59,10 +82,11 @@ pub struct CharacterBundle {
     locked_axis: LockedAxes,
     velocity: Velocity,
     damping: Damping,
+    collision_groups: CollisionGroups,
 }
 
-impl Default for CharacterBundle {
-    fn default() -> Self {
+impl CharacterBundle {
+    pub fn new(collision_groups: CollisionGroups) -> Self {
         Self {
             rigid_body: RigidBody::Dynamic,
             locked_axis: LockedAxes::ROTATION_LOCKED,
@@ -72,6 +96,7 @@ impl Default for CharacterBundle {
                 linear_damping: 10.0,
                 angular_damping: 1.0,
             },
+            collision_groups,
         }
     }
 }
@@ -81,31 +106,40 @@ pub struct PlayerBundle {
     #[bundle]
     character: CharacterBundle,
     player: Player,
-    weapon: Gun,
+    stats: Stats,
+    effects: Effects,
     wave: EnemyWave,
     marker: PlayerMarker,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum PlayerUpgradeEvent {
-    AttackDamage,
-    AttackSpeed,
+    NewWeapon(WeaponKind),
+    /// Applies the next entry of the weapon's level table.
+    LevelUp(Entity),
+    /// Evolves the weapon with the recipe at the given index in `Evolutions`.
+    Evolve(Entity, usize),
+    /// Adds the stat's upgrade modifier from `StatTables`.
+    Stat(Stat),
+    Lifesteal,
+    ChainOnCrit,
+    ExplodeOnKill,
+    SpeedOnDamage,
 }
 
-impl Default for PlayerBundle {
-    fn default() -> Self {
+impl PlayerBundle {
+    fn new(stat_tables: &StatTables) -> Self {
+        let stats = Stats::new(stat_tables.base());
         Self {
-            character: CharacterBundle::default(),
+            character: CharacterBundle::new(PLAYER_COLLISION_GROUPS),
             player: Player {
-                health: PLAYER_HEALTH,
-                speed: PLAYER_SPEED,
+                health: stats.get(Stat::MaxHealth).round() as i32,
                 exp: 0,
+                level: 1,
+                facing: Vec2::X,
             },
-            weapon: Gun {
-                damage: PLAYER_GUN_DAMAGE,
-                range: PLAYER_GUN_RANGE,
-                attack: Timer::from_seconds(PLAYER_ATTACKSPEED, TimerMode::Repeating),
-            },
+            stats,
+            effects: Effects::default(),
             wave: EnemyWave {
                 number: ENEMY_WAVE_NUMBER,
                 radius: ENEMY_WAVE_RADIUS,
@@ -116,20 +150,23 @@ impl Default for PlayerBundle {
     }
 }
 
-fn setup(game_assets: Res<GameAssets>, mut commands: Commands) {
+fn setup(game_assets: Res<GameAssets>, stat_tables: Res<StatTables>, mut commands: Commands) {
     commands
         .spawn(SpriteBundle {
             transform: Transform::from_translation(Vec3::new(0.0, 10.0, 1.0)),
             texture: game_assets.player.clone(),
             ..default()
         })
-        .insert(PlayerBundle::default());
+        .insert(PlayerBundle::new(&stat_tables))
+        .with_children(|builder| {
+            spawn_weapon(builder, WeaponKind::Gun);
+        });
 }
 
 fn player_movement(
     input: Res<Input<KeyCode>>,
     time: Res<Time>,
-    mut player: Query<(&Player, &mut Velocity)>,
+    mut player: Query<(&mut Player, &Stats, &mut Velocity)>,
 ) {
     let mut movement = Vec2::ZERO;
 
@@ -150,62 +187,185 @@ fn player_movement(
         return;
     }
 
-    let movement = movement.normalize() * time.delta().as_secs_f32();
+    let (mut player, stats, mut velocity) = player.single_mut();
+    player.facing = movement.normalize();
 
-    let (player, mut velocity) = player.single_mut();
-    velocity.linvel = movement * player.speed * PLAYER_MOVEMENT_FORCE;
+    let movement = player.facing * time.delta().as_secs_f32();
+    velocity.linvel = movement * stats.get(Stat::MoveSpeed) * PLAYER_MOVEMENT_FORCE;
 }
 
 fn player_exp(
     time: Res<Time>,
     mut commands: Commands,
     mut game_state: ResMut<NextState<GameState>>,
-    mut player: Query<(&Transform, &mut Player), Without<Experience>>,
+    mut player: Query<
+        (&Transform, &Stats, &mut Player),
+        (Without<Experience>, Without<PlayerDying>),
+    >,
     mut exp: Query<(Entity, &Experience, &mut Transform), Without<Player>>,
 ) {
-    let (player_transform, mut player) = player.single_mut();
+    // no more level ups once dying
+    let Ok((player_transform, stats, mut player)) = player.get_single_mut() else {
+        return;
+    };
 
     for (entity, exp, mut transform) in exp.iter_mut() {
         let vec = player_transform.translation - transform.translation;
         let len = vec.length();
         let dir = vec.normalize();
-        if len < PLAYER_PULL_EXP_RANGE {
+        if len < PLAYER_PULL_EXP_RANGE * stats.get(Stat::Magnet) {
             transform.translation += dir * time.delta().as_secs_f32() * EXP_SPEED;
         }
         if len < PLAYER_COLLECT_EXP_RANGE {
             commands.entity(entity).despawn();
-            player.exp += exp.exp;
-            if player.exp % LEVEL_UP_EXP == 0 {
-                player.exp %= LEVEL_UP_EXP;
-                game_state.set(GameState::LevelUp);
-            }
+            player.exp += (exp.exp as f32 * stats.get(Stat::Growth)).round() as u32;
         }
     }
+
+    // one level at a time, the rest follow when back in game
+    if player.exp >= LEVEL_UP_EXP {
+        player.exp -= LEVEL_UP_EXP;
+        player.level += 1;
+        game_state.set(GameState::LevelUp);
+    }
 }
 
 fn player_upgrade(
-    mut gun: Query<&mut Gun, With<Player>>,
+    evolutions: Res<Evolutions>,
+    weapon_levels: Res<WeaponLevels>,
+    stat_tables: Res<StatTables>,
+    mut commands: Commands,
+    mut player: Query<(Entity, &mut Effects, &mut Stats), With<Player>>,
+    mut weapons: Query<(&mut Weapon, &mut Stats, &mut Effects, Option<&Evolved>), Without<Player>>,
     mut player_upgrade_event: EventReader<PlayerUpgradeEvent>,
 ) {
-    let mut gun = gun.single_mut();
+    let (player, mut effects, mut stats) = player.single_mut();
+    let mut weapon_count = weapons.iter().count();
     for event in player_upgrade_event.iter() {
-        match event {
-            PlayerUpgradeEvent::AttackSpeed => {
-                let timer = Timer::from_seconds(
-                    gun.attack.duration().as_secs_f32() - 0.1,
-                    TimerMode::Repeating,
-                );
-                gun.attack = timer;
+        match *event {
+            PlayerUpgradeEvent::NewWeapon(kind) => {
+                if weapon_count < MAX_WEAPONS {
+                    weapon_count += 1;
+                    commands.entity(player).with_children(|builder| {
+                        spawn_weapon(builder, kind);
+                    });
+                }
             }
-            PlayerUpgradeEvent::AttackDamage => gun.damage += 10,
+            PlayerUpgradeEvent::LevelUp(entity) => {
+                let Ok((mut weapon, mut weapon_stats, _, _)) = weapons.get_mut(entity) else {
+                    continue;
+                };
+                let Some(delta) = weapon_levels
+                    .tables
+                    .get(&weapon.kind)
+                    .and_then(|table| table.next(weapon.level))
+                else {
+                    continue;
+                };
+                for modifier in delta.modifiers() {
+                    weapon_stats.add(ModifierSource::Level, modifier);
+                }
+                weapon.level += 1;
+            }
+            PlayerUpgradeEvent::Evolve(entity, recipe) => {
+                let Some(recipe) = evolutions.recipes.get(recipe) else {
+                    continue;
+                };
+                if let Ok((_, mut weapon_stats, mut weapon_effects, None)) = weapons.get_mut(entity)
+                {
+                    weapon_stats.set_source(
+                        ModifierSource::Evolution(entity),
+                        [
+                            (Stat::Damage, recipe.damage as f32),
+                            (Stat::Amount, recipe.amount as f32),
+                        ]
+                        .into_iter()
+                        .filter(|(_, value)| *value != 0.0)
+                        .map(|(stat, value)| Modifier {
+                            stat,
+                            kind: ModifierKind::Add(value),
+                        }),
+                    );
+                    weapon_effects
+                        .effects
+                        .extend(recipe.effects.iter().copied());
+                    stats.set_source(
+                        ModifierSource::Evolution(entity),
+                        recipe.stats.iter().copied(),
+                    );
+                    commands.entity(entity).insert(Evolved {
+                        name: recipe.name.clone(),
+                    });
+                }
+            }
+            PlayerUpgradeEvent::Stat(stat) => {
+                if let Some(upgrade) = stat_tables.tables.get(&stat).and_then(|t| t.upgrade) {
+                    stats.add(
+                        ModifierSource::Upgrade,
+                        Modifier {
+                            stat,
+                            kind: upgrade,
+                        },
+                    );
+                }
+            }
+            PlayerUpgradeEvent::Lifesteal => effects.effects.push((
+                EffectTrigger::Hit,
+                Effect::Lifesteal {
+                    fraction: UPGRADE_LIFESTEAL_FRACTION,
+                },
+            )),
+            PlayerUpgradeEvent::ChainOnCrit => effects.effects.push((
+                EffectTrigger::Crit,
+                Effect::Chain {
+                    damage: UPGRADE_CHAIN_DAMAGE,
+                    range: UPGRADE_CHAIN_RANGE,
+                },
+            )),
+            PlayerUpgradeEvent::ExplodeOnKill => effects.effects.push((
+                EffectTrigger::Kill,
+                Effect::Explosion {
+                    damage: UPGRADE_EXPLOSION_DAMAGE,
+                    radius: UPGRADE_EXPLOSION_RADIUS,
+                },
+            )),
+            PlayerUpgradeEvent::SpeedOnDamage => effects.effects.push((
+                EffectTrigger::DamageTaken,
+                Effect::SpeedBoost {
+                    speed: UPGRADE_SPEED_BOOST,
+                    duration: UPGRADE_SPEED_BOOST_DURATION,
+                },
+            )),
         }
     }
 }
 
-fn player_death(player: Query<&Player>, mut state: ResMut<NextState<GameState>>) {
-    let player = player.single();
+pub fn player_death(
+    mut commands: Commands,
+    mut time: ResMut<Time>,
+    mut state: ResMut<NextState<GameState>>,
+    mut player: Query<(Entity, &Player, Option<&mut PlayerDying>, &mut Sprite)>,
+) {
+    let (entity, player, dying, mut sprite) = player.single_mut();
+
+    let Some(mut dying) = dying else {
+        if player.health <= 0 {
+            commands.entity(entity).insert(PlayerDying {
+                timer: Timer::from_seconds(PLAYER_DEATH_TIME, TimerMode::Once),
+            });
+        }
+        return;
+    };
+
+    // set every frame so hit-stop does not cancel the slow-mo
+    time.set_relative_speed(PLAYER_DEATH_SLOW_MO);
+
+    // ticked in real time, game time is slowed down
+    dying.timer.tick(time.raw_delta());
+    sprite.color = Color::rgba(1.0, 0.2, 0.2, dying.timer.percent_left());
 
-    if player.health <= 0 {
-        state.set(GameState::MainMenu);
+    if dying.timer.finished() {
+        time.set_relative_speed(1.0);
+        state.set(GameState::GameOver);
     }
 }
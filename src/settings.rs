@@ -0,0 +1,21 @@
+use bevy::prelude::*;
+
+#[derive(Debug, Clone, Resource)]
+pub struct Settings {
+    /// Flash enemy sprites white when they take damage.
+    pub hit_flash: bool,
+    /// Briefly slow down time on heavy hits.
+    pub hit_stop: bool,
+    /// Aim with the mouse or the gamepad right stick instead of auto targeting.
+    pub manual_aim: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            hit_flash: true,
+            hit_stop: true,
+            manual_aim: false,
+        }
+    }
+}
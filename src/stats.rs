@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::GameState;
+
+/// Base values and level up upgrades of the player stats.
+const STATS: &str = include_str!("../assets/stats.ron");
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(StatTables::from_ron(STATS).expect("invalid stat tables"))
+            .add_system(timed_modifiers_update.in_set(OnUpdate(GameState::InGame)));
+    }
+}
+
+/// On the player most stats are multipliers for every weapon, on a weapon
+/// they are the weapon's own values, e.g. `Area` in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum Stat {
+    Might,
+    Cooldown,
+    Area,
+    Amount,
+    Duration,
+    /// Projectile speed.
+    Speed,
+    Magnet,
+    Luck,
+    Growth,
+    Curse,
+    MoveSpeed,
+    MaxHealth,
+    // weapon only
+    Damage,
+    CritChance,
+    Pierce,
+    Bounce,
+    Spread,
+}
+
+impl Stat {
+    /// Stats of the player, each one needs a table in `StatTables`.
+    pub const PLAYER: [Stat; 12] = [
+        Stat::Might,
+        Stat::Cooldown,
+        Stat::Area,
+        Stat::Amount,
+        Stat::Duration,
+        Stat::Speed,
+        Stat::Magnet,
+        Stat::Luck,
+        Stat::Growth,
+        Stat::Curse,
+        Stat::MoveSpeed,
+        Stat::MaxHealth,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum ModifierKind {
+    /// Added to the base value.
+    Add(f32),
+    /// Multiplies the base value plus all additions.
+    Mul(f32),
+}
+
+impl ModifierKind {
+    /// Short text for the level up menu, e.g. "+10%".
+    pub fn describe(&self) -> String {
+        match *self {
+            ModifierKind::Add(value) => format!("{value:+}"),
+            ModifierKind::Mul(value) => format!("{:+.0}%", (value - 1.0) * 100.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Modifier {
+    pub stat: Stat,
+    pub kind: ModifierKind,
+}
+
+/// Where a modifier came from, so all modifiers of a source can be replaced at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifierSource {
+    /// Stat upgrades picked on level up.
+    Upgrade,
+    /// Weapon level ups from `WeaponLevels`.
+    Level,
+    /// Bonus of the evolved weapon.
+    Evolution(Entity),
+    /// Timed effects, e.g. a speed boost.
+    Buff,
+    /// The player's curse, on enemies.
+    Curse,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct StatTable {
+    pub base: f32,
+    /// Modifier added by picking the stat on level up.
+    /// Stats without one are never offered.
+    #[serde(default)]
+    pub upgrade: Option<ModifierKind>,
+}
+
+#[derive(Debug, Resource)]
+pub struct StatTables {
+    pub tables: HashMap<Stat, StatTable>,
+}
+
+impl StatTables {
+    /// Parses the tables, requiring one for every player stat.
+    pub fn from_ron(source: &str) -> Result<Self, String> {
+        let tables: HashMap<Stat, StatTable> = ron::from_str(source).map_err(|e| e.to_string())?;
+        for stat in Stat::PLAYER {
+            if !tables.contains_key(&stat) {
+                return Err(format!("missing stat table for {stat:?}"));
+            }
+        }
+        Ok(Self { tables })
+    }
+
+    /// Base values of the player stats.
+    pub fn base(&self) -> impl Iterator<Item = (Stat, f32)> + '_ {
+        self.tables.iter().map(|(stat, table)| (*stat, table.base))
+    }
+}
+
+struct Entry {
+    source: ModifierSource,
+    modifier: Modifier,
+    /// Timed modifiers are removed once this finishes.
+    timer: Option<Timer>,
+}
+
+/// Stats of the player, a weapon or an enemy. Values are recomputed every
+/// time the modifiers change, systems react to that with change detection.
+#[derive(Component)]
+pub struct Stats {
+    base: HashMap<Stat, f32>,
+    modifiers: Vec<Entry>,
+    values: HashMap<Stat, f32>,
+}
+
+impl Stats {
+    pub fn new(base: impl IntoIterator<Item = (Stat, f32)>) -> Self {
+        let base = base.into_iter().collect::<HashMap<_, _>>();
+        Self {
+            values: base.clone(),
+            base,
+            modifiers: Vec::new(),
+        }
+    }
+
+    /// Current value, 0 for stats without a base or modifiers.
+    pub fn get(&self, stat: Stat) -> f32 {
+        self.values.get(&stat).copied().unwrap_or_default()
+    }
+
+    pub fn add(&mut self, source: ModifierSource, modifier: Modifier) {
+        self.modifiers.push(Entry {
+            source,
+            modifier,
+            timer: None,
+        });
+        self.recompute();
+    }
+
+    /// Replaces all modifiers of `source`.
+    pub fn set_source(
+        &mut self,
+        source: ModifierSource,
+        modifiers: impl IntoIterator<Item = Modifier>,
+    ) {
+        self.modifiers.retain(|entry| entry.source != source);
+        self.modifiers
+            .extend(modifiers.into_iter().map(|modifier| Entry {
+                source,
+                modifier,
+                timer: None,
+            }));
+        self.recompute();
+    }
+
+    /// Replaces all modifiers of `source` with one lasting `duration` seconds.
+    pub fn set_timed(&mut self, source: ModifierSource, modifier: Modifier, duration: f32) {
+        self.modifiers.retain(|entry| entry.source != source);
+        self.modifiers.push(Entry {
+            source,
+            modifier,
+            timer: Some(Timer::from_seconds(duration, TimerMode::Once)),
+        });
+        self.recompute();
+    }
+
+    /// Ticks timed modifiers, returns whether any ran out.
+    fn tick(&mut self, delta: std::time::Duration) -> bool {
+        let mut expired = false;
+        for timer in self.modifiers.iter_mut().filter_map(|e| e.timer.as_mut()) {
+            expired |= timer.tick(delta).finished();
+        }
+        expired
+    }
+
+    fn remove_expired(&mut self) {
+        self.modifiers
+            .retain(|entry| !entry.timer.as_ref().is_some_and(Timer::finished));
+        self.recompute();
+    }
+
+    fn recompute(&mut self) {
+        let mut values = self.base.clone();
+        let mut muls = HashMap::<Stat, f32>::new();
+        for entry in self.modifiers.iter() {
+            match entry.modifier.kind {
+                ModifierKind::Add(value) => {
+                    *values.entry(entry.modifier.stat).or_default() += value;
+                }
+                ModifierKind::Mul(value) => {
+                    *muls.entry(entry.modifier.stat).or_insert(1.0) *= value;
+                }
+            }
+        }
+        for (stat, mul) in muls {
+            *values.entry(stat).or_default() *= mul;
+        }
+        self.values = values;
+    }
+}
+
+fn timed_modifiers_update(time: Res<Time>, mut stats: Query<&mut Stats>) {
+    for mut stats in stats.iter_mut() {
+        // ticking alone does not change any value
+        if stats.bypass_change_detection().tick(time.delta()) {
+            stats.remove_expired();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn modifier(stat: Stat, kind: ModifierKind) -> Modifier {
+        Modifier { stat, kind }
+    }
+
+    #[test]
+    fn stat_tables_load() {
+        StatTables::from_ron(STATS).unwrap();
+    }
+
+    #[test]
+    fn missing_stat_table_is_rejected() {
+        let error = StatTables::from_ron("{ Might: (base: 1.0) }").unwrap_err();
+        assert!(error.contains("Cooldown"), "{error}");
+    }
+
+    #[test]
+    fn additions_apply_before_multipliers() {
+        let mut stats = Stats::new([(Stat::Might, 10.0), (Stat::Area, 1.0)]);
+        stats.add(
+            ModifierSource::Upgrade,
+            modifier(Stat::Might, ModifierKind::Mul(2.0)),
+        );
+        stats.add(
+            ModifierSource::Upgrade,
+            modifier(Stat::Might, ModifierKind::Add(5.0)),
+        );
+        stats.add(
+            ModifierSource::Upgrade,
+            modifier(Stat::Might, ModifierKind::Mul(1.5)),
+        );
+        assert_eq!(stats.get(Stat::Might), 45.0);
+        assert_eq!(stats.get(Stat::Area), 1.0);
+        // stats without a base start at 0
+        stats.add(
+            ModifierSource::Upgrade,
+            modifier(Stat::Amount, ModifierKind::Add(2.0)),
+        );
+        assert_eq!(stats.get(Stat::Amount), 2.0);
+    }
+
+    #[test]
+    fn set_source_replaces_only_its_own_modifiers() {
+        let evolution = ModifierSource::Evolution(Entity::from_raw(0));
+        let mut stats = Stats::new([(Stat::Might, 10.0)]);
+        stats.add(
+            ModifierSource::Upgrade,
+            modifier(Stat::Might, ModifierKind::Add(1.0)),
+        );
+        stats.set_source(evolution, [modifier(Stat::Might, ModifierKind::Add(2.0))]);
+        assert_eq!(stats.get(Stat::Might), 13.0);
+        stats.set_source(evolution, [modifier(Stat::Might, ModifierKind::Add(3.0))]);
+        assert_eq!(stats.get(Stat::Might), 14.0);
+        stats.set_source(evolution, []);
+        assert_eq!(stats.get(Stat::Might), 11.0);
+    }
+
+    #[test]
+    fn timed_modifiers_expire() {
+        let mut stats = Stats::new([(Stat::MoveSpeed, 100.0)]);
+        let boost = modifier(Stat::MoveSpeed, ModifierKind::Add(50.0));
+        stats.set_timed(ModifierSource::Buff, boost, 1.0);
+        assert_eq!(stats.get(Stat::MoveSpeed), 150.0);
+
+        assert!(!stats.tick(Duration::from_secs_f32(0.5)));
+        // a new buff of the same source restarts the duration
+        stats.set_timed(ModifierSource::Buff, boost, 1.0);
+        assert!(!stats.tick(Duration::from_secs_f32(0.6)));
+        assert_eq!(stats.get(Stat::MoveSpeed), 150.0);
+
+        assert!(stats.tick(Duration::from_secs_f32(0.5)));
+        stats.remove_expired();
+        assert_eq!(stats.get(Stat::MoveSpeed), 100.0);
+    }
+}
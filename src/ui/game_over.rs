@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::{combat_stats::CombatStats, player::Player, utils::remove_all_with, GameState};
+
+use super::{spawn_button, UiConfig, UiState};
+
+pub struct UiGameOverPlugin;
+
+impl Plugin for UiGameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setup.in_schedule(OnEnter(UiState::GameOver)))
+            .add_system(button_system.in_set(OnUpdate(UiState::GameOver)))
+            .add_system(remove_all_with::<UiGameOverMarker>.in_schedule(OnExit(UiState::GameOver)));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+struct UiGameOverMarker;
+
+#[derive(Debug, Clone, Copy, Component)]
+enum UiGameOverButton {
+    Retry,
+    MainMenu,
+}
+
+fn setup(
+    mut commands: Commands,
+    config: Res<UiConfig>,
+    stats: Res<CombatStats>,
+    player: Query<&Player>,
+) {
+    let player = player.single();
+
+    let mut results = format!(
+        "Time survived: {:02}:{:02}\nLevel: {}\nKills: {}\n",
+        stats.time as u32 / 60,
+        stats.time as u32 % 60,
+        player.level,
+        stats.total_kills(),
+    );
+    let mut weapons = stats.weapons.iter().collect::<Vec<_>>();
+    weapons.sort_by_key(|(_, weapon)| std::cmp::Reverse(weapon.damage));
+    for (kind, weapon) in weapons {
+        results += &format!(
+            "{kind:?}: {} damage, {} kills, {} shots, {} hits\n",
+            weapon.damage, weapon.kills, weapon.shots, weapon.hits
+        );
+    }
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                ..config.menu_style.clone()
+            },
+            background_color: config.menu_color.into(),
+            ..default()
+        })
+        .insert(UiGameOverMarker)
+        .with_children(|builder| {
+            builder
+                .spawn(TextBundle {
+                    text: Text::from_section(results, config.text_style.clone()),
+                    ..default()
+                })
+                .insert(UiGameOverMarker);
+            builder
+                .spawn(NodeBundle {
+                    background_color: config.menu_color.into(),
+                    ..default()
+                })
+                .insert(UiGameOverMarker)
+                .with_children(|builder| {
+                    spawn_button(builder, &config, UiGameOverButton::Retry, UiGameOverMarker);
+                    spawn_button(
+                        builder,
+                        &config,
+                        UiGameOverButton::MainMenu,
+                        UiGameOverMarker,
+                    );
+                });
+        });
+}
+
+fn button_system(
+    style: Res<UiConfig>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut interaction_query: Query<
+        (&UiGameOverButton, &Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (button, interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                *color = style.button_color_pressed.into();
+                match button {
+                    UiGameOverButton::Retry => game_state.set(GameState::InGame),
+                    UiGameOverButton::MainMenu => game_state.set(GameState::MainMenu),
+                }
+            }
+            Interaction::Hovered => {
+                *color = style.button_color_hover.into();
+            }
+            Interaction::None => {
+                *color = style.button_color_normal.into();
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+use crate::{combat_stats::CombatStats, utils::remove_all_with};
+
+use super::{UiConfig, UiState};
+
+pub struct UiInGamePlugin;
+
+impl Plugin for UiInGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setup.in_schedule(OnEnter(UiState::InGame)))
+            .add_system(update_dps_meter.in_set(OnUpdate(UiState::InGame)))
+            .add_system(remove_all_with::<UiInGameMarker>.in_schedule(OnExit(UiState::InGame)));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+struct UiInGameMarker;
+
+#[derive(Debug, Clone, Copy, Component)]
+struct UiDpsMeter;
+
+fn setup(mut commands: Commands, config: Res<UiConfig>) {
+    commands
+        .spawn(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(10.0),
+                    top: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            },
+            text: Text::from_section("", config.text_style.clone()),
+            ..default()
+        })
+        .insert(UiDpsMeter)
+        .insert(UiInGameMarker);
+}
+
+fn update_dps_meter(stats: Res<CombatStats>, mut meter: Query<&mut Text, With<UiDpsMeter>>) {
+    let mut text = meter.single_mut();
+    let mut weapons = stats.weapons.iter().collect::<Vec<_>>();
+    weapons.sort_by(|(_, a), (_, b)| b.dps.total_cmp(&a.dps));
+    text.sections[0].value = weapons
+        .iter()
+        .map(|(kind, weapon)| {
+            format!(
+                "{kind:?}: {:.1} dps, {} dmg, {} kills, {} shots, {} hits\n",
+                weapon.dps, weapon.damage, weapon.kills, weapon.shots, weapon.hits
+            )
+        })
+        .collect();
+}
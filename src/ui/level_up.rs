@@ -1,8 +1,21 @@
 use bevy::prelude::*;
+use rand::seq::SliceRandom;
 
-use crate::{player::PlayerUpgradeEvent, utils::remove_all_with, GameState};
+use crate::{
+    evolution::{Evolutions, Evolved},
+    guns::{Weapon, WeaponKind, WeaponLevels, MAX_WEAPONS},
+    player::PlayerUpgradeEvent,
+    stats::{Stat, StatTables, Stats},
+    utils::remove_all_with,
+    GameState,
+};
 
-use super::{spawn_button, UiConfig, UiState};
+use super::{spawn_text_button, UiConfig, UiState};
+
+/// Number of upgrades offered on each level up.
+pub const LEVEL_UP_OPTIONS: usize = 4;
+/// Number of random stat upgrades put among the candidates.
+pub const LEVEL_UP_STAT_CANDIDATES: usize = 2;
 
 pub struct UiLevelUpPlugin;
 
@@ -18,12 +31,83 @@ impl Plugin for UiLevelUpPlugin {
 struct UiLevelUpMarker;
 
 #[derive(Debug, Clone, Copy, Component)]
-enum UiLevelUpButton {
-    AttackSpeed,
-    AttackDamage,
-}
+struct UiLevelUpButton(PlayerUpgradeEvent);
+
+fn setup(
+    mut commands: Commands,
+    config: Res<UiConfig>,
+    evolutions: Res<Evolutions>,
+    weapon_levels: Res<WeaponLevels>,
+    stat_tables: Res<StatTables>,
+    weapons: Query<(Entity, &Weapon, &Stats, Option<&Evolved>)>,
+) {
+    let owned = weapons
+        .iter()
+        .map(|(_, weapon, _, _)| weapon.kind)
+        .collect::<Vec<_>>();
+
+    // evolutions are always offered
+    let mut evolve_options = Vec::new();
+    for (entity, weapon, stats, evolved) in weapons.iter() {
+        if evolved.is_some() {
+            continue;
+        }
+        for (i, recipe) in evolutions.recipes.iter().enumerate() {
+            if recipe.available(weapon, stats, &owned) {
+                evolve_options.push((
+                    format!("Evolve {:?} into {}", weapon.kind, recipe.name),
+                    PlayerUpgradeEvent::Evolve(entity, i),
+                ));
+            }
+        }
+    }
+
+    let mut options = Vec::new();
+    for (entity, weapon, _, evolved) in weapons.iter() {
+        let Some(table) = weapon_levels.tables.get(&weapon.kind) else {
+            continue;
+        };
+        let Some(next) = table.next(weapon.level) else {
+            continue;
+        };
+        let name = evolved.map_or_else(|| format!("{:?}", weapon.kind), |e| e.name.clone());
+        options.push((
+            format!(
+                "{name} Lv {}/{}: {}",
+                weapon.level + 1,
+                table.max_level(),
+                next.describe()
+            ),
+            PlayerUpgradeEvent::LevelUp(entity),
+        ));
+    }
+    if weapons.iter().count() < MAX_WEAPONS {
+        for kind in WeaponKind::ALL {
+            if !owned.contains(&kind) {
+                options.push((format!("New {kind:?}"), PlayerUpgradeEvent::NewWeapon(kind)));
+            }
+        }
+    }
+    let stat_options = Stat::PLAYER
+        .iter()
+        .filter_map(|stat| {
+            let upgrade = stat_tables.tables.get(stat)?.upgrade?;
+            Some((
+                format!("{stat:?} {}", upgrade.describe()),
+                PlayerUpgradeEvent::Stat(*stat),
+            ))
+        })
+        .collect::<Vec<_>>();
+    options.extend(
+        stat_options
+            .choose_multiple(&mut rand::thread_rng(), LEVEL_UP_STAT_CANDIDATES)
+            .cloned(),
+    );
+    options.push(("Lifesteal".into(), PlayerUpgradeEvent::Lifesteal));
+    options.push(("ChainOnCrit".into(), PlayerUpgradeEvent::ChainOnCrit));
+    options.push(("ExplodeOnKill".into(), PlayerUpgradeEvent::ExplodeOnKill));
+    options.push(("SpeedOnDamage".into(), PlayerUpgradeEvent::SpeedOnDamage));
 
-fn setup(mut commands: Commands, config: Res<UiConfig>) {
     commands
         .spawn(NodeBundle {
             style: config.menu_style.clone(),
@@ -32,18 +116,20 @@ fn setup(mut commands: Commands, config: Res<UiConfig>) {
         })
         .insert(UiLevelUpMarker)
         .with_children(|builder| {
-            spawn_button(
-                builder,
-                &config,
-                UiLevelUpButton::AttackSpeed,
-                UiLevelUpMarker,
-            );
-            spawn_button(
-                builder,
-                &config,
-                UiLevelUpButton::AttackDamage,
-                UiLevelUpMarker,
-            );
+            let random = LEVEL_UP_OPTIONS.saturating_sub(evolve_options.len());
+            for (text, upgrade) in evolve_options
+                .iter()
+                .take(LEVEL_UP_OPTIONS)
+                .chain(options.choose_multiple(&mut rand::thread_rng(), random))
+            {
+                spawn_text_button(
+                    builder,
+                    &config,
+                    text.clone(),
+                    UiLevelUpButton(*upgrade),
+                    UiLevelUpMarker,
+                );
+            }
         });
 }
 
@@ -60,16 +146,8 @@ fn button_system(
         match *interaction {
             Interaction::Clicked => {
                 *color = style.button_color_pressed.into();
-                match button {
-                    UiLevelUpButton::AttackSpeed => {
-                        player_upgrade_event.send(PlayerUpgradeEvent::AttackSpeed);
-                        game_state.set(GameState::InGame);
-                    }
-                    UiLevelUpButton::AttackDamage => {
-                        player_upgrade_event.send(PlayerUpgradeEvent::AttackDamage);
-                        game_state.set(GameState::InGame);
-                    }
-                }
+                player_upgrade_event.send(button.0);
+                game_state.set(GameState::InGame);
             }
             Interaction::Hovered => {
                 *color = style.button_color_hover.into();
@@ -6,8 +6,11 @@ use crate::{
     GameState,
 };
 
+mod game_over;
+mod in_game;
 mod level_up;
 mod main_menu;
+mod settings;
 
 pub struct UiPlugin;
 
@@ -27,8 +30,15 @@ impl Plugin for UiPlugin {
                 set_state::<UiState, { UiState::LevelUp as u8 }>
                     .in_schedule(OnEnter(GameState::LevelUp)),
             )
+            .add_system(
+                set_state::<UiState, { UiState::GameOver as u8 }>
+                    .in_schedule(OnEnter(GameState::GameOver)),
+            )
+            .add_plugin(game_over::UiGameOverPlugin)
+            .add_plugin(in_game::UiInGamePlugin)
             .add_plugin(level_up::UiLevelUpPlugin)
-            .add_plugin(main_menu::UiMainMenuPlugin);
+            .add_plugin(main_menu::UiMainMenuPlugin)
+            .add_plugin(settings::UiSettingsPlugin);
     }
 }
 
@@ -39,6 +49,7 @@ enum UiState {
     Settings,
     InGame,
     LevelUp,
+    GameOver,
 }
 impl_into_state!(UiState);
 
@@ -84,6 +95,20 @@ fn spawn_button<B, M>(child_builder: &mut ChildBuilder, style: &UiConfig, button
 where
     B: Component + std::fmt::Debug,
     M: Component + Copy,
+{
+    let text = format!("{button:?}");
+    spawn_text_button(child_builder, style, text, button, marker);
+}
+
+fn spawn_text_button<B, M>(
+    child_builder: &mut ChildBuilder,
+    style: &UiConfig,
+    text: String,
+    button: B,
+    marker: M,
+) where
+    B: Component,
+    M: Component + Copy,
 {
     child_builder
         .spawn(ButtonBundle {
@@ -94,7 +119,7 @@ where
         .with_children(|parent| {
             parent
                 .spawn(TextBundle {
-                    text: Text::from_section(format!("{button:?}"), style.text_style.clone()),
+                    text: Text::from_section(text, style.text_style.clone()),
                     ..default()
                 })
                 .insert(marker);
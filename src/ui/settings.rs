@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+
+use crate::{settings::Settings, utils::remove_all_with};
+
+use super::{spawn_button, UiConfig, UiState};
+
+pub struct UiSettingsPlugin;
+
+impl Plugin for UiSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setup.in_schedule(OnEnter(UiState::Settings)))
+            .add_systems((button_system, update_labels).in_set(OnUpdate(UiState::Settings)))
+            .add_system(remove_all_with::<UiSettingsMarker>.in_schedule(OnExit(UiState::Settings)));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+struct UiSettingsMarker;
+
+#[derive(Debug, Clone, Copy, Component)]
+enum UiSettingsButton {
+    HitFlash,
+    HitStop,
+    ManualAim,
+    Back,
+}
+
+fn setup(mut commands: Commands, config: Res<UiConfig>) {
+    commands
+        .spawn(NodeBundle {
+            style: config.menu_style.clone(),
+            background_color: config.menu_color.into(),
+            ..default()
+        })
+        .insert(UiSettingsMarker)
+        .with_children(|builder| {
+            spawn_button(
+                builder,
+                &config,
+                UiSettingsButton::HitFlash,
+                UiSettingsMarker,
+            );
+            spawn_button(
+                builder,
+                &config,
+                UiSettingsButton::HitStop,
+                UiSettingsMarker,
+            );
+            spawn_button(
+                builder,
+                &config,
+                UiSettingsButton::ManualAim,
+                UiSettingsMarker,
+            );
+            spawn_button(builder, &config, UiSettingsButton::Back, UiSettingsMarker);
+        });
+}
+
+fn button_system(
+    style: Res<UiConfig>,
+    mut settings: ResMut<Settings>,
+    mut ui_state: ResMut<NextState<UiState>>,
+    mut interaction_query: Query<
+        (&UiSettingsButton, &Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (button, interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                *color = style.button_color_pressed.into();
+                match button {
+                    UiSettingsButton::HitFlash => settings.hit_flash = !settings.hit_flash,
+                    UiSettingsButton::HitStop => settings.hit_stop = !settings.hit_stop,
+                    UiSettingsButton::ManualAim => settings.manual_aim = !settings.manual_aim,
+                    UiSettingsButton::Back => ui_state.set(UiState::MainMenu),
+                }
+            }
+            Interaction::Hovered => {
+                *color = style.button_color_hover.into();
+            }
+            Interaction::None => {
+                *color = style.button_color_normal.into();
+            }
+        }
+    }
+}
+
+fn update_labels(
+    settings: Res<Settings>,
+    buttons: Query<(&UiSettingsButton, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (button, children) in buttons.iter() {
+        let value = match button {
+            UiSettingsButton::HitFlash => settings.hit_flash,
+            UiSettingsButton::HitStop => settings.hit_stop,
+            UiSettingsButton::ManualAim => settings.manual_aim,
+            UiSettingsButton::Back => continue,
+        };
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value =
+                    format!("{button:?}: {}", if value { "On" } else { "Off" });
+            }
+        }
+    }
+}